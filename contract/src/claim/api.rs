@@ -1,17 +1,23 @@
-use near_sdk::{env, ext_contract, json_types::U128, near_bindgen, AccountId, PromiseOrValue};
+use near_sdk::{env, ext_contract, json_types::U128, near_bindgen, require, AccountId, PromiseOrValue};
 use sweat_jar_model::{
-    api::ClaimApi, claimed_amount_view::ClaimedAmountView, jar::AggregatedTokenAmountView, TokenAmount, JAR_BATCH_SIZE,
+    api::ClaimApi,
+    claimed_amount_view::ClaimedAmountView,
+    jar::{AggregatedTokenAmountView, ClaimOperatorView, ClaimTarget, JarView, LockOperation},
+    TokenAmount, JAR_BATCH_SIZE,
 };
 
 use crate::{
     common::Timestamp,
-    event::{emit, ClaimEventItem, EventKind},
+    event::{emit, ClaimEventItem, ClaimForData, ClaimOperatorData, ClaimToJarData, EventKind},
     internal::is_promise_success,
-    jar::model::Jar,
+    jar::{account::v4::ClaimOperator, model::Jar},
     score::AccountScore,
-    Contract, ContractExt, JarsStorage,
+    Contract, ContractExt, JarsStorage, ProductsStorage,
 };
 
+/// Limits storage used by claim operators of a single account.
+const MAX_CLAIM_OPERATORS: usize = 10;
+
 #[allow(dead_code)] // False positive since rust 1.78. It is used from `ext_contract` macro.
 #[ext_contract(ext_self)]
 pub trait ClaimCallbacks {
@@ -31,33 +37,178 @@ impl ClaimApi for Contract {
         let account_id = env::predecessor_account_id();
         self.assert_account_is_not_migrating(&account_id);
         self.migrate_account_if_needed(&account_id);
-        self.claim_jars_internal(account_id, detailed)
+        self.claim_jars_internal(account_id.clone(), &account_id, detailed, EventKind::Claim)
+    }
+
+    fn claim_to_jar(&mut self, target: ClaimTarget) -> JarView {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+        self.claim_to_jar_internal(account_id, target)
+    }
+
+    fn claim_for(&mut self, account_id: AccountId, detailed: Option<bool>) -> PromiseOrValue<ClaimedAmountView> {
+        let operator_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let operator = self
+            .accounts
+            .get(&account_id)
+            .and_then(|account| {
+                account
+                    .claim_operators
+                    .iter()
+                    .find(|operator| operator.operator_id == operator_id)
+                    .cloned()
+            })
+            .unwrap_or_else(|| env::panic_str("Caller is not a claim operator of the account"));
+
+        let receiver_id = operator.receiver_id.unwrap_or_else(|| account_id.clone());
+
+        self.claim_jars_internal(account_id.clone(), &receiver_id.clone(), detailed, |claimed| {
+            EventKind::ClaimFor(ClaimForData {
+                operator_id,
+                account_id,
+                receiver_id,
+                claimed,
+            })
+        })
+    }
+
+    fn approve_claim_operator(&mut self, operator_id: AccountId, receiver_id: Option<AccountId>) {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        require!(operator_id != account_id, "Account can't be its own claim operator");
+
+        let account = self
+            .accounts
+            .get_mut(&account_id)
+            .unwrap_or_else(|| env::panic_str("Account doesn't have jars"));
+
+        let claim_operators = &mut account.claim_operators;
+
+        match claim_operators
+            .iter()
+            .position(|operator| operator.operator_id == operator_id)
+        {
+            Some(index) => claim_operators[index].receiver_id.clone_from(&receiver_id),
+            None => {
+                require!(
+                    claim_operators.len() < MAX_CLAIM_OPERATORS,
+                    format!("Account can't have more than {MAX_CLAIM_OPERATORS} claim operators")
+                );
+
+                claim_operators.push(ClaimOperator {
+                    operator_id: operator_id.clone(),
+                    receiver_id: receiver_id.clone(),
+                });
+            }
+        }
+
+        emit(EventKind::ApproveClaimOperator(ClaimOperatorData {
+            account_id,
+            operator_id,
+            receiver_id,
+        }));
+    }
+
+    fn revoke_claim_operator(&mut self, operator_id: AccountId) {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let claim_operators = &mut self
+            .accounts
+            .get_mut(&account_id)
+            .unwrap_or_else(|| env::panic_str("Account doesn't have jars"))
+            .claim_operators;
+
+        let index = claim_operators
+            .iter()
+            .position(|operator| operator.operator_id == operator_id)
+            .unwrap_or_else(|| env::panic_str("Operator is not approved"));
+
+        let operator = claim_operators.remove(index);
+
+        emit(EventKind::RevokeClaimOperator(ClaimOperatorData {
+            account_id,
+            operator_id,
+            receiver_id: operator.receiver_id,
+        }));
+    }
+
+    fn get_claim_operators(&self, account_id: AccountId) -> Vec<ClaimOperatorView> {
+        self.accounts.get(&account_id).map_or(vec![], |account| {
+            account
+                .claim_operators
+                .iter()
+                .map(|operator| ClaimOperatorView {
+                    operator_id: operator.operator_id.clone(),
+                    receiver_id: operator.receiver_id.clone(),
+                })
+                .collect()
+        })
     }
 }
 
 impl Contract {
+    /// Claims interest of `account_id` jars and transfers it to `receiver_id`.
+    /// `event` builds the event emitted after a successful transfer from claimed amounts.
     fn claim_jars_internal(
         &mut self,
         account_id: AccountId,
+        receiver_id: &AccountId,
         detailed: Option<bool>,
+        event: impl FnOnce(Vec<ClaimEventItem>) -> EventKind,
     ) -> PromiseOrValue<ClaimedAmountView> {
         let now = env::block_timestamp_ms();
         let mut accumulator = ClaimedAmountView::new(detailed);
 
-        let account_jars = self.account_jars(&account_id);
+        let account_score_before_transfer = self.get_score(&account_id).copied();
 
-        let account_score = self.get_score_mut(&account_id);
+        let (event_data, jars_before_transfer) = self.claim_accrued_interest(&account_id, now);
 
-        let account_score_before_transfer = account_score.as_ref().map(|s| **s);
+        for (jar_id, amount) in &event_data {
+            self.get_jar_mut_internal(&account_id, *jar_id)
+                .lock(LockOperation::Claim, now);
+            accumulator.add(*jar_id, amount.0);
+        }
 
-        let score = account_score.map(AccountScore::claim_score).unwrap_or_default();
+        if accumulator.get_total().0 > 0 {
+            self.claim_interest(
+                receiver_id,
+                accumulator,
+                jars_before_transfer,
+                account_score_before_transfer,
+                event(event_data),
+                now,
+            )
+        } else {
+            PromiseOrValue::Value(accumulator)
+        }
+    }
+
+    /// Moves interest of up to `JAR_BATCH_SIZE` unlocked jars with the most interest to their claimed balance.
+    /// Returns claimed amounts and the state of the jars before claiming.
+    fn claim_accrued_interest(&mut self, account_id: &AccountId, now: Timestamp) -> (Vec<ClaimEventItem>, Vec<Jar>) {
+        let account_jars = self.account_jars(account_id);
+
+        let streak_threshold = self.streak_threshold(&account_jars);
+
+        let score = self
+            .get_score_mut(account_id)
+            .map(|score| score.claim_score(streak_threshold))
+            .unwrap_or_default();
 
         let mut unlocked_jars: Vec<((TokenAmount, u64), &Jar)> = account_jars
             .iter()
             .filter(|jar| !jar.is_pending_withdraw)
             .map(|jar| {
                 let product = self.get_product(&jar.product_id);
-                (jar.get_interest(&score, &product, now), jar)
+                (jar.get_interest(&score, product, now), jar)
             })
             .collect();
 
@@ -65,34 +216,70 @@ impl Contract {
 
         let jars_to_claim: Vec<_> = unlocked_jars.into_iter().take(JAR_BATCH_SIZE).collect();
 
-        let mut event_data: Vec<ClaimEventItem> = vec![];
+        let mut claimed: Vec<ClaimEventItem> = vec![];
 
         for ((available_interest, remainder), jar) in &jars_to_claim {
             if *available_interest > 0 {
+                let product = self.get_product(&jar.product_id).clone();
                 let jar = self.get_jar_mut_internal(&jar.account_id, jar.id);
 
                 jar.claim_remainder = *remainder;
 
-                jar.claim(*available_interest, now).lock();
+                jar.claim(*available_interest, &product, now);
 
-                accumulator.add(jar.id, *available_interest);
-
-                event_data.push((jar.id, U128(*available_interest)));
+                claimed.push((jar.id, U128(*available_interest)));
             }
         }
 
-        if accumulator.get_total().0 > 0 {
-            self.claim_interest(
-                &account_id,
-                accumulator,
-                jars_to_claim.into_iter().map(|a| a.1).cloned().collect(),
-                account_score_before_transfer,
-                EventKind::Claim(event_data),
-                now,
-            )
-        } else {
-            PromiseOrValue::Value(accumulator)
+        (claimed, jars_to_claim.into_iter().map(|a| a.1).cloned().collect())
+    }
+
+    /// Puts claimed interest into a jar instead of transferring it.
+    /// The tokens never leave the contract, so a dedicated event is emitted instead of `Claim` and `TopUp`/`CreateJar`.
+    fn claim_to_jar_internal(&mut self, account_id: AccountId, target: ClaimTarget) -> JarView {
+        if let ClaimTarget::Stake(stake) = &target {
+            require!(
+                self.get_product(&stake.ticket.product_id).public_key.is_none(),
+                "Claiming to a new jar is not supported for products which require a signature"
+            );
+        }
+
+        let now = env::block_timestamp_ms();
+
+        let (claimed, _) = self.claim_accrued_interest(&account_id, now);
+        let amount: TokenAmount = claimed.iter().map(|(_, amount)| amount.0).sum();
+
+        require!(amount > 0, "Nothing to claim");
+
+        let jar = match target {
+            ClaimTarget::TopUp(jar_id) => self.top_up_internal(&account_id, jar_id.0, amount),
+            ClaimTarget::Stake(stake) => self.create_jar_internal(account_id.clone(), stake.ticket, amount, None),
+        };
+
+        let score = self
+            .get_score(&account_id)
+            .map(AccountScore::claimable_score)
+            .unwrap_or_default();
+
+        for (jar_id, _) in &claimed {
+            if *jar_id == jar.id {
+                continue;
+            }
+
+            let claimed_jar = self.get_jar_internal(&account_id, *jar_id);
+
+            if claimed_jar.should_be_closed(&score, self.get_product(&claimed_jar.product_id), now) {
+                self.delete_jar(&account_id, *jar_id);
+            }
         }
+
+        emit(EventKind::ClaimToJar(ClaimToJarData {
+            claimed,
+            amount: U128(amount),
+            jar: jar.clone().into(),
+        }));
+
+        jar.into()
     }
 }
 
@@ -100,7 +287,7 @@ impl Contract {
     #[cfg(test)]
     fn claim_interest(
         &mut self,
-        _account_id: &AccountId,
+        _receiver_id: &AccountId,
         claimed_amount: ClaimedAmountView,
         jars_before_transfer: Vec<Jar>,
         score_before_transfer: Option<AccountScore>,
@@ -121,7 +308,7 @@ impl Contract {
     #[mutants::skip] // Covered by integration tests
     fn claim_interest(
         &mut self,
-        account_id: &AccountId,
+        receiver_id: &AccountId,
         claimed_amount: ClaimedAmountView,
         jars_before_transfer: Vec<Jar>,
         score_before_transfer: Option<AccountScore>,
@@ -139,7 +326,7 @@ impl Contract {
         });
 
         self.ft_contract()
-            .ft_transfer(account_id, claimed_amount.get_total().0, "claim", &None)
+            .ft_transfer(receiver_id, claimed_amount.get_total().0, "claim", &None)
             .then(after_claim_call(
                 claimed_amount,
                 jars_before_transfer,
@@ -161,9 +348,7 @@ impl Contract {
     ) -> ClaimedAmountView {
         if is_promise_success {
             for jar_before_transfer in jars_before_transfer {
-                let product = self.products.get(&jar_before_transfer.product_id).unwrap_or_else(|| {
-                    env::panic_str(&format!("Product '{}' doesn't exist", jar_before_transfer.product_id))
-                });
+                let product = self.products.get_product(&jar_before_transfer.product_id);
 
                 let score = self
                     .get_score(&jar_before_transfer.account_id)
@@ -180,7 +365,7 @@ impl Contract {
 
                 jar.unlock();
 
-                if jar.should_be_closed(&score, &product, now) {
+                if jar.should_be_closed(&score, product, now) {
                     self.delete_jar(&jar_before_transfer.account_id, jar_before_transfer.id);
                 }
             }
@@ -1,17 +1,22 @@
 #![cfg(test)]
 
-use near_sdk::{json_types::U128, test_utils::test_env::alice, PromiseOrValue};
+use near_sdk::{
+    json_types::{U128, U64},
+    test_utils::test_env::{alice, bob},
+    AccountId, PromiseOrValue,
+};
 use sweat_jar_model::{
-    api::{ClaimApi, WithdrawApi},
+    api::{ClaimApi, JarApi, WithdrawApi},
     claimed_amount_view::ClaimedAmountView,
-    ScoreRecord, UDecimal, U32,
+    jar::{ClaimOperatorView, ClaimTarget, JarTicket, StakeTarget},
+    ScoreRecord, UDecimal, MS_IN_YEAR, U32,
 };
 
 use crate::{
     common::{test_data::set_test_future_success, tests::Context},
     jar::model::Jar,
     product::model::{Apy, Product},
-    test_utils::{admin, UnwrapPromise},
+    test_utils::{admin, expect_panic, UnwrapPromise, PRINCIPAL},
 };
 
 #[test]
@@ -231,3 +236,180 @@ fn failed_future_claim() {
 
     assert_eq!(jar_before_claim, jar_after_claim);
 }
+
+#[test]
+fn claim_to_existing_jar() {
+    let alice = alice();
+
+    let product = Product::new().flexible().cap(0, 10 * PRINCIPAL);
+    let mut context = Context::new(admin())
+        .with_products(&[product])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(&alice);
+
+    expect_panic(&context, "Nothing to claim", || {
+        context.contract().claim_to_jar(ClaimTarget::TopUp(U32(0)));
+    });
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+    let jar = context.contract().claim_to_jar(ClaimTarget::TopUp(U32(0)));
+
+    assert_eq!(jar.principal.0, PRINCIPAL + 120_000);
+    assert_eq!(jar.claimed_balance.0, 120_000);
+    assert_eq!(context.contract().get_total_interest(alice).amount.total.0, 0);
+}
+
+#[test]
+fn claim_to_jar_without_top_up() {
+    let alice = alice();
+
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new()])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(&alice);
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+    expect_panic(&context, "The product doesn't allow top-ups", || {
+        context.contract().claim_to_jar(ClaimTarget::TopUp(U32(0)));
+    });
+}
+
+#[test]
+fn claim_to_new_jar() {
+    let alice = alice();
+
+    let new_product = Product::new().id("new_product");
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new(), new_product.clone()])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(&alice);
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+    let jar = context.contract().claim_to_jar(ClaimTarget::Stake(StakeTarget {
+        ticket: JarTicket {
+            product_id: new_product.id.clone(),
+            valid_until: U64(u64::MAX),
+            timezone: None,
+        },
+    }));
+
+    assert_eq!(jar.product_id, new_product.id);
+    assert_eq!(jar.principal.0, 120_000);
+
+    let jars = context.contract().get_jars_for_account(alice);
+
+    assert_eq!(jars.len(), 2);
+    assert_eq!(jars[0].principal.0, PRINCIPAL);
+    assert_eq!(jars[0].claimed_balance.0, 120_000);
+}
+
+#[test]
+fn claim_to_new_jar_of_signed_product() {
+    let alice = alice();
+
+    let signed_product = Product::new().id("signed_product").public_key(vec![1; 32]);
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new(), signed_product.clone()])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(&alice);
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+    expect_panic(
+        &context,
+        "Claiming to a new jar is not supported for products which require a signature",
+        || {
+            context.contract().claim_to_jar(ClaimTarget::Stake(StakeTarget {
+                ticket: JarTicket {
+                    product_id: signed_product.id.clone(),
+                    valid_until: U64(u64::MAX),
+                    timezone: None,
+                },
+            }));
+        },
+    );
+
+    assert_eq!(context.contract().get_total_interest(alice).amount.total.0, 120_000);
+}
+
+#[test]
+fn claim_for_account_by_operator() {
+    let alice = alice();
+    let operator = bob();
+
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new()])
+        .with_jars(&[Jar::new(0)]);
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+    context.switch_account(&operator);
+    expect_panic(&context, "Caller is not a claim operator of the account", || {
+        context.contract().claim_for(alice.clone(), None);
+    });
+
+    context.switch_account(&alice);
+    context.contract().approve_claim_operator(operator.clone(), None);
+
+    assert_eq!(
+        context.contract().get_claim_operators(alice.clone()),
+        vec![ClaimOperatorView {
+            operator_id: operator.clone(),
+            receiver_id: None,
+        }]
+    );
+
+    context.switch_account(&operator);
+    let claimed = context.contract().claim_for(alice.clone(), None).unwrap();
+
+    assert_eq!(claimed.get_total().0, 120_000);
+    assert_eq!(context.contract().get_jar(alice, U32(0)).claimed_balance.0, 120_000);
+}
+
+#[test]
+fn approve_and_revoke_claim_operator() {
+    let alice = alice();
+    let operator = bob();
+    let receiver: AccountId = "receiver".parse().unwrap();
+
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new()])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(&alice);
+
+    expect_panic(&context, "Account can't be its own claim operator", || {
+        context.contract().approve_claim_operator(alice.clone(), None);
+    });
+
+    context.contract().approve_claim_operator(operator.clone(), None);
+    context
+        .contract()
+        .approve_claim_operator(operator.clone(), Some(receiver.clone()));
+
+    assert_eq!(
+        context.contract().get_claim_operators(alice.clone()),
+        vec![ClaimOperatorView {
+            operator_id: operator.clone(),
+            receiver_id: Some(receiver),
+        }]
+    );
+
+    context.contract().revoke_claim_operator(operator.clone());
+    assert!(context.contract().get_claim_operators(alice.clone()).is_empty());
+
+    expect_panic(&context, "Operator is not approved", || {
+        context.contract().revoke_claim_operator(operator.clone());
+    });
+
+    context.switch_account(&operator);
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+    expect_panic(&context, "Caller is not a claim operator of the account", || {
+        context.contract().claim_for(alice.clone(), None);
+    });
+}
@@ -7,6 +7,10 @@ pub type Timestamp = u64;
 /// Duration in milliseconds
 pub type Duration = u64;
 
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
 pub mod gas_data {
     use near_sdk::Gas;
 
@@ -31,12 +35,27 @@ pub mod gas_data {
     /// Value is measured with `measure_withdraw_all`
     /// 10 `TGas` was enough for 200 jars. 15 here just in case.
     pub(crate) const GAS_FOR_BULK_AFTER_WITHDRAW: Gas = Gas::from_tgas(15);
+
+    /// Gas attached to `ft_transfer_call` in `withdraw_and_call`.
+    /// It has to cover `ft_on_transfer` of the receiver and `ft_resolve_transfer` of the token contract.
+    pub(crate) const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_tgas(60);
+
+    /// Callback is similar to `after_withdraw` but also handles the refund and may move a closed jar to the archive.
+    /// Fee transfers scheduled by the callback are paid on top of it with `GAS_FOR_FT_TRANSFER` each,
+    /// plus `GAS_FOR_AFTER_WITHDRAW` for `after_withdraw_and_call_fee` that settles the jar after them.
+    /// Value is checked with `measure_after_withdraw_and_call_test`. 2 `TGas` more than `after_withdraw` just in case.
+    pub(crate) const GAS_FOR_AFTER_WITHDRAW_AND_CALL: Gas = Gas::from_tgas(6);
+
+    /// Gas attached to `migrate_state` call after the staged upgrade is deployed.
+    /// Migration steps rewrite the whole contract state, so it is generous.
+    pub(crate) const GAS_FOR_STATE_MIGRATION: Gas = Gas::from_tgas(150);
 }
 
 #[cfg(test)]
 mod test {
     use crate::common::gas_data::{
-        GAS_FOR_AFTER_CLAIM, GAS_FOR_AFTER_WITHDRAW, GAS_FOR_BULK_AFTER_WITHDRAW, GAS_FOR_FT_TRANSFER,
+        GAS_FOR_AFTER_CLAIM, GAS_FOR_AFTER_WITHDRAW, GAS_FOR_AFTER_WITHDRAW_AND_CALL, GAS_FOR_BULK_AFTER_WITHDRAW,
+        GAS_FOR_FT_TRANSFER, GAS_FOR_FT_TRANSFER_CALL, GAS_FOR_STATE_MIGRATION,
     };
 
     #[test]
@@ -45,5 +64,8 @@ mod test {
         assert_eq!(GAS_FOR_AFTER_CLAIM.as_gas(), 20_000_000_000_000);
         assert_eq!(GAS_FOR_AFTER_WITHDRAW.as_gas(), 4_000_000_000_000);
         assert_eq!(GAS_FOR_BULK_AFTER_WITHDRAW.as_gas(), 15_000_000_000_000);
+        assert_eq!(GAS_FOR_FT_TRANSFER_CALL.as_gas(), 60_000_000_000_000);
+        assert_eq!(GAS_FOR_AFTER_WITHDRAW_AND_CALL.as_gas(), 6_000_000_000_000);
+        assert_eq!(GAS_FOR_STATE_MIGRATION.as_gas(), 150_000_000_000_000);
     }
 }
@@ -5,6 +5,8 @@ use std::{
     sync::{Mutex, MutexGuard},
 };
 
+use sweat_jar_model::TokenAmount;
+
 use crate::internal::is_promise_success;
 
 type ThreadId = String;
@@ -25,6 +27,7 @@ static DATA: TestDataStorage = TestDataStorage {
 
 const FUTURE_SUCCESS_KEY: &str = "FUTURE_SUCCESS_KEY";
 const LOG_EVENTS_KEY: &str = "LOG_EVENTS_KEY";
+const TRANSFER_CALL_REFUND_KEY: &str = "TRANSFER_CALL_REFUND_KEY";
 
 fn data() -> MutexGuard<'static, Map> {
     DATA.data.lock().unwrap()
@@ -72,6 +75,27 @@ pub(crate) fn should_log_events() -> bool {
     value.parse().unwrap()
 }
 
+/// Amount which the receiver of `ft_transfer_call` returns back to the contract
+pub(crate) fn set_test_transfer_call_refund(amount: TokenAmount) {
+    let mut data = data();
+    let map = data.entry(thread_name()).or_default();
+    map.insert(TRANSFER_CALL_REFUND_KEY.to_owned(), amount.to_string());
+}
+
+pub(crate) fn get_test_transfer_call_refund() -> TokenAmount {
+    let data = data();
+
+    let Some(map) = data.get(&thread_name()) else {
+        return 0;
+    };
+
+    let Some(value) = map.get(TRANSFER_CALL_REFUND_KEY) else {
+        return 0;
+    };
+
+    value.parse().unwrap()
+}
+
 fn thread_name() -> String {
     std::thread::current().name().unwrap().to_owned()
 }
@@ -54,7 +54,9 @@ impl Context {
 
     pub(crate) fn with_products(self, products: &[Product]) -> Self {
         for product in products {
-            self.contract().products.insert(&product.id, product);
+            self.contract()
+                .products
+                .insert(product.id.clone(), product.clone().into());
         }
 
         self
@@ -139,6 +141,11 @@ impl Context {
         self.set_deposit_yocto(0);
     }
 
+    pub(crate) fn set_input(&mut self, input: Vec<u8>) {
+        self.builder.input(input);
+        testing_env!(self.builder.build());
+    }
+
     pub(crate) fn set_deposit_yocto(&mut self, amount: Balance) {
         self.builder.attached_deposit(NearToken::from_yoctonear(amount));
         testing_env!(self.builder.build());
@@ -2,7 +2,12 @@ use near_sdk::{
     json_types::{Base64VecU8, U128},
     log, near, serde_json, AccountId,
 };
-use sweat_jar_model::{jar::JarId, Local, ProductId, Score, TokenAmount, U32, UTC};
+use sweat_jar_model::{
+    jar::JarId,
+    upgrade::StagedUpgradeView,
+    withdraw::{FeeTransfer, WithdrawView},
+    Local, ProductId, Score, TokenAmount, U32, UTC,
+};
 
 use crate::{
     common::Timestamp,
@@ -19,19 +24,38 @@ pub enum EventKind {
     RegisterProduct(Product),
     CreateJar(EventJar),
     Claim(Vec<ClaimEventItem>),
+    ClaimToJar(ClaimToJarData),
+    ClaimFor(ClaimForData),
+    ApproveClaimOperator(ClaimOperatorData),
+    RevokeClaimOperator(ClaimOperatorData),
     Withdraw(WithdrawData),
     WithdrawAll(Vec<WithdrawData>),
+    WithdrawAndCall(WithdrawAndCallData),
+    RequestUnbond(UnbondData),
+    FinalizeUnbond(WithdrawData),
+    CancelUnbond(UnbondData),
+    SplitFee(Vec<FeeSplitData>),
     Migration(Vec<MigrationEventItem>),
     Restake(RestakeData),
     RestakeAll(Vec<RestakeData>),
+    RestakeInto(RestakeIntoData),
+    SetAutoRenew(AutoRenewData),
+    ReleaseStuckLocks(Vec<JarId>),
+    SetJarArchive(bool),
+    PruneClosedJars(Vec<JarId>),
     ApplyPenalty(PenaltyData),
     BatchApplyPenalty(BatchPenaltyData),
+    ApplyScorePenalty(PenaltyData),
+    BatchApplyScorePenalty(BatchPenaltyData),
     EnableProduct(EnableProductData),
     ChangeProductPublicKey(ChangeProductPublicKeyData),
     TopUp(TopUpData),
     RecordScore(Vec<ScoreData>),
     OldScoreWarning((Score, Local)),
     JarsMerge(AccountId),
+    StageUpgrade(StagedUpgradeView),
+    CancelUpgrade(StagedUpgradeView),
+    ExecuteUpgrade(StagedUpgradeView),
 }
 
 #[derive(Debug)]
@@ -76,9 +100,68 @@ struct SweatJarEvent {
 /// `JarId` and interest to claim
 pub type ClaimEventItem = (JarId, U128);
 
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct ClaimToJarData {
+    /// Interest claimed from each jar
+    pub claimed: Vec<ClaimEventItem>,
+    /// Total claimed interest added to the target jar
+    pub amount: U128,
+    /// State of the target jar after the operation
+    pub jar: EventJar,
+}
+
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct ClaimForData {
+    /// Account which triggered the claim
+    pub operator_id: AccountId,
+    pub account_id: AccountId,
+    /// Account which received the claimed tokens
+    pub receiver_id: AccountId,
+    pub claimed: Vec<ClaimEventItem>,
+}
+
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct ClaimOperatorData {
+    pub account_id: AccountId,
+    pub operator_id: AccountId,
+    pub receiver_id: Option<AccountId>,
+}
+
 /// (id, fee, amount)
 pub type WithdrawData = (JarId, U128, U128);
 
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct WithdrawAndCallData {
+    pub id: JarId,
+    pub receiver_id: AccountId,
+    pub fee: U128,
+    /// Amount used by the receiver
+    pub amount: U128,
+    /// Amount returned back to the jar
+    pub refund: U128,
+}
+
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct UnbondData {
+    pub id: JarId,
+    /// Total amount of tokens in unbonding
+    pub amount: U128,
+    pub unlock_at: Timestamp,
+}
+
+/// Distribution of a jar's withdrawal fee between beneficiaries
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct FeeSplitData {
+    pub id: JarId,
+    pub transfers: Vec<FeeTransfer>,
+}
+
 #[derive(Debug)]
 #[near(serializers=[json])]
 pub struct MigrationEventItem {
@@ -90,6 +173,21 @@ pub struct MigrationEventItem {
 /// (`old_id`, `new_id`)
 pub type RestakeData = (JarId, JarId);
 
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct RestakeIntoData {
+    pub old_id: JarId,
+    pub new_id: JarId,
+    pub product_id: ProductId,
+}
+
+#[derive(Debug)]
+#[near(serializers=[json])]
+pub struct AutoRenewData {
+    pub id: JarId,
+    pub auto_renew: bool,
+}
+
 #[derive(Debug)]
 #[near(serializers=[json])]
 pub struct PenaltyData {
@@ -132,6 +230,7 @@ pub struct TopUpData {
 pub struct ScoreData {
     pub account_id: AccountId,
     pub score: Vec<(U32, UTC)>,
+    pub streak: u16,
 }
 
 impl From<EventKind> for SweatJarEvent {
@@ -158,6 +257,22 @@ pub(crate) fn emit(event: EventKind) {
     }
 }
 
+/// Reports distribution of fees for jars whose product splits the withdrawal fee.
+pub(crate) fn emit_fee_split(jars: Vec<(JarId, &WithdrawView)>) {
+    let data: Vec<_> = jars
+        .into_iter()
+        .filter(|(_, view)| !view.fee_split.is_empty())
+        .map(|(id, view)| FeeSplitData {
+            id,
+            transfers: view.fee_split.clone(),
+        })
+        .collect();
+
+    if !data.is_empty() {
+        emit(EventKind::SplitFee(data));
+    }
+}
+
 impl SweatJarEvent {
     fn to_json_string(&self) -> String {
         serde_json::to_string_pretty(self)
@@ -212,7 +327,7 @@ mod test {
 
         assert_eq!(
             SweatJarEvent::from(EventKind::CreateJar(
-                Jar::V1(JarLastVersion {
+                Jar::V6(JarLastVersion {
                     id: 555,
                     account_id: "bob.near".to_string().try_into().unwrap(),
                     product_id: "some_product".to_string(),
@@ -223,6 +338,11 @@ mod test {
                     is_pending_withdraw: false,
                     is_penalty_applied: false,
                     claim_remainder: 55555,
+                    unbonding: None,
+                    auto_renew: false,
+                    lock: None,
+                    total_deposited: 78685678567,
+                    term_started_at: 1234324235,
                 })
                 .into()
             ))
@@ -269,10 +389,12 @@ mod test {
                 ScoreData {
                     account_id: AccountId::from_str("alice.near").unwrap(),
                     score: vec![(10.into(), 10.into())],
+                    streak: 0,
                 },
                 ScoreData {
                     account_id: AccountId::from_str("bob.near").unwrap(),
                     score: vec![(20.into(), 20.into())],
+                    streak: 3,
                 }
             ]))
             .to_json_event_string(),
@@ -288,7 +410,8 @@ mod test {
           "10",
           10
         ]
-      ]
+      ],
+      "streak": 0
     },
     {
       "account_id": "bob.near",
@@ -297,7 +420,8 @@ mod test {
           "20",
           20
         ]
-      ]
+      ],
+      "streak": 3
     }
   ]
 }"#
@@ -27,6 +27,7 @@ impl Contract {
 pub(crate) trait FungibleTokenInterface {
     fn ft_transfer(&self, receiver_id: &AccountId, amount: u128, memo: &str, fee: &Option<Fee>) -> Promise;
     fn ft_transfer_call(&self, receiver_id: &AccountId, amount: u128, memo: &str, msg: &str, tgas: u64) -> Promise;
+    fn ft_transfer_fee(&self, fee: &Fee, memo: &str) -> Promise;
 }
 
 impl FungibleTokenInterface for FungibleTokenContract {
@@ -34,8 +35,8 @@ impl FungibleTokenInterface for FungibleTokenContract {
     fn ft_transfer(&self, receiver_id: &AccountId, amount: u128, memo: &str, fee: &Option<Fee>) -> Promise {
         if let Some(fee) = fee {
             Promise::new(self.address.clone())
-                .ft_transfer(receiver_id, amount - fee.amount, Some(memo.to_string()))
-                .ft_transfer(&fee.beneficiary_id, fee.amount, Some(format!("{memo} fee")))
+                .ft_transfer(receiver_id, amount - fee.amount(), Some(memo.to_string()))
+                .ft_transfer_fee(fee, memo)
         } else {
             Promise::new(self.address.clone()).ft_transfer(receiver_id, amount, Some(memo.to_string()))
         }
@@ -51,10 +52,16 @@ impl FungibleTokenInterface for FungibleTokenContract {
             tgas,
         )
     }
+
+    #[mutants::skip] // Covered by integration tests
+    fn ft_transfer_fee(&self, fee: &Fee, memo: &str) -> Promise {
+        Promise::new(self.address.clone()).ft_transfer_fee(fee, memo)
+    }
 }
 
 trait FungibleTokenPromise {
     fn ft_transfer(self, receiver_id: &AccountId, amount: TokenAmount, memo: Option<String>) -> Promise;
+    fn ft_transfer_fee(self, fee: &Fee, memo: &str) -> Promise;
     fn ft_transfer_call(
         self,
         receiver_id: &AccountId,
@@ -83,6 +90,18 @@ impl FungibleTokenPromise for Promise {
         )
     }
 
+    /// Transfers each part of a split fee to its beneficiary. Empty parts are skipped
+    /// because fungible token contracts reject transfers of zero amount.
+    #[mutants::skip] // Covered by integration tests
+    fn ft_transfer_fee(self, fee: &Fee, memo: &str) -> Promise {
+        fee.transfers
+            .iter()
+            .filter(|transfer| transfer.amount.0 > 0)
+            .fold(self, |promise, transfer| {
+                promise.ft_transfer(&transfer.beneficiary_id, transfer.amount.0, Some(format!("{memo} fee")))
+            })
+    }
+
     #[mutants::skip] // Covered by integration tests
     fn ft_transfer_call(
         self,
@@ -6,7 +6,7 @@ use sweat_jar_model::{
     ProductId,
 };
 
-use crate::{env, jar::model::Jar, AccountId, Contract, Product};
+use crate::{env, jar::model::Jar, AccountId, Contract, Product, ProductsStorage};
 
 impl Contract {
     pub(crate) fn assert_manager(&self) {
@@ -89,18 +89,8 @@ impl Contract {
         jars.push(jar);
     }
 
-    // UnorderedMap doesn't have cache and deserializes `Product` on each get
-    // This cached getter significantly reduces gas usage
-    pub fn get_product(&self, product_id: &ProductId) -> Product {
-        self.products_cache
-            .borrow_mut()
-            .entry(product_id.clone())
-            .or_insert_with(|| {
-                self.products
-                    .get(product_id)
-                    .unwrap_or_else(|| env::panic_str(&format!("Product '{product_id}' doesn't exist")))
-            })
-            .clone()
+    pub fn get_product(&self, product_id: &ProductId) -> &Product {
+        self.products.get_product(product_id)
     }
 }
 
@@ -123,19 +113,7 @@ pub(crate) fn assert_gas<Message: Display>(gas_needed: u64, error: impl FnOnce()
 mod test {
     use near_sdk::env;
 
-    use crate::{
-        common::tests::Context,
-        internal::assert_gas,
-        test_utils::{admin, expect_panic},
-    };
-
-    #[test]
-    #[should_panic(expected = r#"Can be performed only by admin"#)]
-    fn self_update_without_access() {
-        let admin = admin();
-        let context = Context::new(admin);
-        context.contract().update_contract(vec![], None);
-    }
+    use crate::{internal::assert_gas, test_utils::expect_panic};
 
     #[test]
     fn test_assert_gas() {
@@ -1,4 +1,8 @@
 pub mod v1;
+pub mod v2;
+pub mod v3;
+pub mod v4;
+pub mod v5;
 pub mod versioned;
 
-pub type AccountJarsLastVersion = v1::AccountV1;
+pub type AccountJarsLastVersion = v5::AccountV5;
@@ -0,0 +1,26 @@
+use near_sdk::near;
+use sweat_jar_model::jar::JarId;
+
+use crate::{
+    jar::{account::v3::AccountV3, model::Jar},
+    score::AccountScoreV2,
+};
+
+#[near]
+#[derive(Debug, PartialEq)]
+pub struct AccountV2 {
+    /// The last jar ID. Is used as nonce in `get_ticket_hash` method.
+    pub last_id: JarId,
+    pub jars: Vec<Jar>,
+    pub score: AccountScoreV2,
+}
+
+impl From<AccountV2> for AccountV3 {
+    fn from(value: AccountV2) -> Self {
+        Self {
+            last_id: value.last_id,
+            jars: value.jars,
+            score: value.score.into(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use near_sdk::near;
+use sweat_jar_model::jar::JarId;
+
+use crate::{
+    jar::{account::v4::AccountV4, model::Jar},
+    score::AccountScore,
+};
+
+#[near]
+#[derive(Debug, PartialEq)]
+pub struct AccountV3 {
+    /// The last jar ID. Is used as nonce in `get_ticket_hash` method.
+    pub last_id: JarId,
+    pub jars: Vec<Jar>,
+    pub score: AccountScore,
+}
+
+impl From<AccountV3> for AccountV4 {
+    fn from(value: AccountV3) -> Self {
+        Self {
+            last_id: value.last_id,
+            jars: value.jars,
+            score: value.score,
+            claim_operators: vec![],
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use near_sdk::{near, AccountId};
+use sweat_jar_model::jar::JarId;
+
+use crate::{
+    jar::{account::v5::AccountV5, model::Jar},
+    score::AccountScore,
+};
+
+#[near]
+#[derive(Debug, PartialEq)]
+pub struct AccountV4 {
+    /// The last jar ID. Is used as nonce in `get_ticket_hash` method.
+    pub last_id: JarId,
+    pub jars: Vec<Jar>,
+    pub score: AccountScore,
+    /// Accounts allowed to claim interest on behalf of this account.
+    pub claim_operators: Vec<ClaimOperator>,
+}
+
+/// An account allowed to trigger claims on behalf of the jars owner.
+#[near]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimOperator {
+    pub operator_id: AccountId,
+    /// Account which receives claimed tokens instead of the owner, if approved by the owner.
+    pub receiver_id: Option<AccountId>,
+}
+
+impl From<AccountV4> for AccountV5 {
+    fn from(value: AccountV4) -> Self {
+        Self {
+            last_id: value.last_id,
+            jars: value.jars,
+            score: value.score,
+            claim_operators: value.claim_operators,
+            closed_jars: None,
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::ops::{Deref, DerefMut};
+
+use near_sdk::near;
+use sweat_jar_model::{jar::JarId, ProductId, TokenAmount};
+
+use crate::{
+    common::Timestamp,
+    jar::{
+        account::v4::ClaimOperator,
+        model::{AccountJarsLegacy, Jar},
+    },
+    migration::account_jars_non_versioned::AccountJarsNonVersioned,
+    score::AccountScore,
+};
+
+/// Limits storage used by the archive of closed jars of a single account.
+pub(crate) const MAX_CLOSED_JARS: usize = 50;
+
+#[near]
+#[derive(Default, Debug, PartialEq)]
+pub struct AccountV5 {
+    /// The last jar ID. Is used as nonce in `get_ticket_hash` method.
+    pub last_id: JarId,
+    pub jars: Vec<Jar>,
+    pub score: AccountScore,
+    /// Accounts allowed to claim interest on behalf of this account.
+    pub claim_operators: Vec<ClaimOperator>,
+    /// Jars removed from the account. Is `None` unless the owner enabled the archive.
+    pub closed_jars: Option<Vec<ClosedJar>>,
+}
+
+/// A summary of a deleted jar, kept to show past stakes of the account.
+#[near]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClosedJar {
+    pub id: JarId,
+    pub product_id: ProductId,
+    /// The total amount of tokens deposited into the jar.
+    pub principal: TokenAmount,
+    /// The total amount of interest claimed from the jar.
+    pub claimed_balance: TokenAmount,
+    pub closed_at: Timestamp,
+}
+
+impl AccountV5 {
+    /// Records a deleted jar if the archive is on. The oldest record is dropped when the archive is full.
+    pub(crate) fn archive(&mut self, jar: &Jar, now: Timestamp) {
+        let Some(closed_jars) = &mut self.closed_jars else {
+            return;
+        };
+
+        if closed_jars.len() >= MAX_CLOSED_JARS {
+            closed_jars.remove(0);
+        }
+
+        closed_jars.push(ClosedJar {
+            id: jar.id,
+            product_id: jar.product_id.clone(),
+            principal: jar.total_deposited,
+            claimed_balance: jar.claimed_balance,
+            closed_at: now,
+        });
+    }
+}
+
+impl Deref for AccountV5 {
+    type Target = Vec<Jar>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.jars
+    }
+}
+
+impl DerefMut for AccountV5 {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.jars
+    }
+}
+
+impl From<AccountJarsLegacy> for AccountV5 {
+    fn from(value: AccountJarsLegacy) -> Self {
+        Self {
+            last_id: value.last_id,
+            jars: value.jars.into_iter().map(Into::into).collect(),
+            score: AccountScore::default(),
+            claim_operators: vec![],
+            closed_jars: None,
+        }
+    }
+}
+
+impl From<AccountJarsNonVersioned> for AccountV5 {
+    fn from(value: AccountJarsNonVersioned) -> Self {
+        Self {
+            last_id: value.last_id,
+            jars: value.jars,
+            score: AccountScore::default(),
+            claim_operators: vec![],
+            closed_jars: None,
+        }
+    }
+}
@@ -7,7 +7,7 @@ use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::{
     jar::{
-        account::{v1::AccountV1, AccountJarsLastVersion},
+        account::{v1::AccountV1, v2::AccountV2, v3::AccountV3, v4::AccountV4, v5::AccountV5, AccountJarsLastVersion},
         model::AccountJarsLegacy,
     },
     migration::account_jars_non_versioned::AccountJarsNonVersioned,
@@ -20,6 +20,10 @@ pub type Account = AccountVersioned;
 #[borsh(crate = "near_sdk::borsh")]
 pub enum AccountVersioned {
     V1(AccountV1),
+    V2(AccountV2),
+    V3(AccountV3),
+    V4(AccountV4),
+    V5(AccountV5),
 }
 
 impl AccountVersioned {
@@ -51,7 +55,13 @@ impl BorshDeserialize for AccountVersioned {
         let tag: u8 = BorshDeserialize::deserialize_reader(reader)?;
 
         let result = match tag {
-            0 => AccountVersioned::V1(BorshDeserialize::deserialize_reader(reader)?),
+            0 => AccountVersioned::V5(
+                AccountV4::from(AccountV3::from(AccountV2::from(AccountV1::deserialize_reader(reader)?))).into(),
+            ),
+            1 => AccountVersioned::V5(AccountV4::from(AccountV3::from(AccountV2::deserialize_reader(reader)?)).into()),
+            2 => AccountVersioned::V5(AccountV4::from(AccountV3::deserialize_reader(reader)?).into()),
+            3 => AccountVersioned::V5(AccountV4::deserialize_reader(reader)?.into()),
+            4 => AccountVersioned::V5(BorshDeserialize::deserialize_reader(reader)?),
             // Add new versions here:
             _ => return Err(Error::new(InvalidData, format!("Unexpected variant tag: {tag:?}"))),
         };
@@ -62,7 +72,7 @@ impl BorshDeserialize for AccountVersioned {
 
 impl Default for AccountVersioned {
     fn default() -> Self {
-        Self::V1(AccountV1::default())
+        Self::V5(AccountV5::default())
     }
 }
 
@@ -70,9 +80,11 @@ impl Deref for AccountVersioned {
     type Target = AccountJarsLastVersion;
     fn deref(&self) -> &Self::Target {
         match self {
-            Self::V1(jars) => jars,
-            // Guaranteed by `BorshDeserialize` implementation
-            // Self::V2(jar) => jar, <- Add new version here
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) => {
+                unreachable!("Guaranteed by `BorshDeserialize` implementation")
+            }
+            Self::V5(account) => account,
+            // Self::V6(account) => account, <- Add new version here
         }
     }
 }
@@ -80,21 +92,118 @@ impl Deref for AccountVersioned {
 impl DerefMut for AccountVersioned {
     fn deref_mut(&mut self) -> &mut Self::Target {
         match self {
-            Self::V1(jars) => jars,
-            // Guaranteed by `BorshDeserialize` implementation
-            // Self::V2(jar) => jar, <- Add new version here
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) => {
+                unreachable!("Guaranteed by `BorshDeserialize` implementation")
+            }
+            Self::V5(account) => account,
+            // Self::V6(account) => account, <- Add new version here
         }
     }
 }
 
 impl From<AccountJarsLegacy> for Account {
     fn from(value: AccountJarsLegacy) -> Self {
-        Self::V1(value.into())
+        Self::V5(value.into())
     }
 }
 
 impl From<AccountJarsNonVersioned> for Account {
     fn from(value: AccountJarsNonVersioned) -> Self {
-        Self::V1(value.into())
+        Self::V5(value.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use near_sdk::borsh::{to_vec, BorshDeserialize};
+    use sweat_jar_model::{Timezone, UTC};
+
+    use crate::{
+        jar::account::{
+            v1::AccountV1, v2::AccountV2, v3::AccountV3, v4::AccountV4, v5::AccountV5, versioned::AccountVersioned,
+        },
+        score::{AccountScore, AccountScoreV1, AccountScoreV2},
+    };
+
+    fn upgraded(account: &AccountVersioned) -> AccountVersioned {
+        AccountVersioned::try_from_slice(&to_vec(account).unwrap()).unwrap()
+    }
+
+    fn expected_account(scores: [u32; 2]) -> AccountVersioned {
+        AccountVersioned::V5(AccountV5 {
+            last_id: 5,
+            jars: vec![],
+            score: AccountScore {
+                updated: UTC(100),
+                timezone: Timezone::hour_shift(3),
+                scores,
+                scores_history: [2_000, 3_000],
+                streak: 0,
+            },
+            claim_operators: vec![],
+            closed_jars: None,
+        })
+    }
+
+    #[test]
+    fn account_with_legacy_score_is_upgraded_on_read() {
+        let v1 = AccountVersioned::V1(AccountV1 {
+            last_id: 5,
+            jars: vec![],
+            score: AccountScoreV1 {
+                updated: UTC(100),
+                timezone: Timezone::hour_shift(3),
+                scores: [1_000, u16::MAX],
+                scores_history: [2_000, 3_000],
+            },
+        });
+
+        assert_eq!(upgraded(&v1), expected_account([1_000, 65_535]));
+
+        let v2 = AccountVersioned::V2(AccountV2 {
+            last_id: 5,
+            jars: vec![],
+            score: AccountScoreV2 {
+                updated: UTC(100),
+                timezone: Timezone::hour_shift(3),
+                scores: [1_000, 100_000],
+                scores_history: [2_000, 3_000],
+            },
+        });
+
+        assert_eq!(upgraded(&v2), expected_account([1_000, 100_000]));
+
+        let v3 = AccountVersioned::V3(AccountV3 {
+            last_id: 5,
+            jars: vec![],
+            score: AccountScore {
+                updated: UTC(100),
+                timezone: Timezone::hour_shift(3),
+                scores: [1_000, 100_000],
+                scores_history: [2_000, 3_000],
+                streak: 0,
+            },
+        });
+
+        assert_eq!(upgraded(&v3), expected_account([1_000, 100_000]));
+
+        let v4 = AccountVersioned::V4(AccountV4 {
+            last_id: 5,
+            jars: vec![],
+            score: AccountScore {
+                updated: UTC(100),
+                timezone: Timezone::hour_shift(3),
+                scores: [1_000, 100_000],
+                scores_history: [2_000, 3_000],
+                streak: 0,
+            },
+            claim_operators: vec![],
+        });
+
+        assert_eq!(upgraded(&v4), expected_account([1_000, 100_000]));
+
+        let latest = expected_account([1_000, 100_000]);
+
+        assert_eq!(upgraded(&latest), latest);
     }
 }
@@ -1,15 +1,27 @@
 use std::collections::HashMap;
 
-use near_sdk::{env, env::panic_str, json_types::U128, near_bindgen, require, AccountId};
+use near_sdk::{
+    env,
+    env::panic_str,
+    json_types::{Base64VecU8, U128, U64},
+    near_bindgen, require, AccountId,
+};
 use sweat_jar_model::{
     api::JarApi,
-    jar::{AggregatedInterestView, AggregatedTokenAmountView, JarId, JarIdView, JarView},
-    TokenAmount, JAR_BATCH_SIZE, U32,
+    export::{AccountExportView, ScoreExportView, ACCOUNT_EXPORT_VERSION},
+    jar::{
+        AggregatedInterestView, AggregatedTokenAmountView, ClosedJarView, JarId, JarIdView, JarTicket, JarView,
+        StuckLockView,
+    },
+    ProductId, TokenAmount, JAR_BATCH_SIZE, U32,
 };
 
 use crate::{
-    event::{emit, EventKind},
+    assert::assert_not_locked,
+    common::Timestamp,
+    event::{emit, AutoRenewData, EventKind, RestakeIntoData},
     jar::model::Jar,
+    product::model::Product,
     score::AccountScore,
     Contract, ContractExt, JarsStorage,
 };
@@ -17,7 +29,7 @@ use crate::{
 impl Contract {
     fn can_be_restaked(&self, jar: &Jar, now: u64) -> bool {
         let product = self.get_product(&jar.product_id);
-        !jar.is_empty() && product.is_enabled && product.allows_restaking() && jar.is_liquidable(&product, now)
+        !jar.is_empty() && product.is_enabled && product.allows_restaking() && jar.is_liquidable(product, now)
     }
 
     fn restake_internal(&mut self, jar_id: JarIdView) -> (JarId, JarView) {
@@ -30,7 +42,7 @@ impl Contract {
 
         let jar = self.get_jar_internal(&account_id, jar_id);
 
-        let product = self.get_product(&jar.product_id);
+        let product = self.get_product(&jar.product_id).clone();
 
         require!(product.allows_restaking(), "The product doesn't support restaking");
         require!(product.is_enabled, "The product is disabled");
@@ -39,34 +51,51 @@ impl Contract {
         require!(jar.is_liquidable(&product, now), "The jar is not mature yet");
         require!(!jar.is_empty(), "The jar is empty, nothing to restake");
 
-        let principal = jar.principal;
-
-        let new_jar = Jar::create(
+        let new_jar = self.move_principal_to_new_jar(
+            &account_id,
+            &jar,
+            &product,
             restaked_jar_id,
-            jar.account_id.clone(),
             jar.product_id.clone(),
-            principal,
             now,
         );
 
+        (jar_id, new_jar.into())
+    }
+
+    /// Moves the whole principal of a mature `jar` into a new jar of `product_id` with `new_jar_id`.
+    /// The old jar is deleted if there is no interest left to claim.
+    fn move_principal_to_new_jar(
+        &mut self,
+        account_id: &AccountId,
+        jar: &Jar,
+        product: &Product,
+        new_jar_id: JarId,
+        product_id: ProductId,
+        now: Timestamp,
+    ) -> Jar {
+        let principal = jar.principal;
+
+        let new_jar = Jar::create(new_jar_id, jar.account_id.clone(), product_id, principal, now);
+
         let score = self
-            .get_score(&account_id)
+            .get_score(account_id)
             .map(AccountScore::claimable_score)
             .unwrap_or_default();
 
-        let withdraw_jar = jar.withdrawn(&score, &product, principal, now);
-        let should_be_closed = withdraw_jar.should_be_closed(&score, &product, now);
+        let withdraw_jar = jar.withdrawn(&score, product, principal, now);
+        let should_be_closed = withdraw_jar.should_be_closed(&score, product, now);
 
         if should_be_closed {
             self.delete_jar(&withdraw_jar.account_id, withdraw_jar.id);
         } else {
             let jar_id = withdraw_jar.id;
-            *self.get_jar_mut_internal(&account_id, jar_id) = withdraw_jar;
+            *self.get_jar_mut_internal(account_id, jar_id) = withdraw_jar;
         }
 
-        self.add_new_jar(&account_id, new_jar.clone());
+        self.add_new_jar(account_id, new_jar.clone());
 
-        (jar_id, new_jar.into())
+        new_jar
     }
 }
 
@@ -109,6 +138,37 @@ impl JarApi for Contract {
         self.account_jars(&account_id).iter().map(Into::into).collect()
     }
 
+    fn export_account(&self, account_id: AccountId) -> AccountExportView {
+        let now = env::block_timestamp_ms();
+
+        let account_score = self.get_score(&account_id);
+        let score = account_score.map(AccountScore::claimable_score).unwrap_or_default();
+
+        let mut jars: Vec<_> = self
+            .account_jars(&account_id)
+            .iter()
+            .map(|jar| jar.export(self.get_product(&jar.product_id), &score, now))
+            .collect();
+        jars.sort_by_key(|jar| jar.id.0);
+
+        AccountExportView {
+            version: ACCOUNT_EXPORT_VERSION,
+            account_id,
+            block_height: env::block_height().into(),
+            timestamp: now.into(),
+            total_principal: U128(jars.iter().map(|jar| jar.principal.0).sum()),
+            total_accrued_interest: U128(jars.iter().map(|jar| jar.accrued_interest.0).sum()),
+            jars,
+            score: account_score.map(|score| ScoreExportView {
+                updated: score.updated,
+                timezone: score.timezone,
+                scores: score.scores.to_vec(),
+                scores_history: score.scores_history.to_vec(),
+                streak: score.streak,
+            }),
+        }
+    }
+
     fn get_total_principal(&self, account_id: AccountId) -> AggregatedTokenAmountView {
         self.get_principal(
             self.account_jars(&account_id).iter().map(|a| U32(a.id)).collect(),
@@ -155,7 +215,7 @@ impl JarApi for Contract {
         for jar in self.account_jars_with_ids(&account_id, &jar_ids) {
             let product = self.get_product(&jar.product_id);
 
-            let interest = jar.get_interest(&score, &product, now).0;
+            let interest = jar.get_interest(&score, product, now).0;
 
             detailed_amounts.insert(U32(jar.id), U128(interest));
             total_amount += interest;
@@ -222,6 +282,78 @@ impl JarApi for Contract {
         result
     }
 
+    fn restake_into(&mut self, jar_id: JarIdView, ticket: JarTicket, signature: Option<Base64VecU8>) -> JarView {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let jar_id = jar_id.0;
+        let jar = self.get_jar_internal(&account_id, jar_id);
+        let product = self.get_product(&jar.product_id).clone();
+        let now = env::block_timestamp_ms();
+
+        assert_not_locked(&jar);
+        require!(jar.is_liquidable(&product, now), "The jar is not mature yet");
+        require!(!jar.is_empty(), "The jar is empty, nothing to restake");
+
+        let target_product = self.get_product(&ticket.product_id).clone();
+
+        target_product.assert_enabled();
+        target_product.assert_cap(jar.principal);
+
+        self.verify(&account_id, jar.principal, &ticket, signature);
+
+        if target_product.is_score_product() {
+            self.init_account_score(&account_id, ticket.timezone);
+        }
+
+        let new_jar_id = self.increment_and_get_last_jar_id();
+        let new_jar = self.move_principal_to_new_jar(&account_id, &jar, &product, new_jar_id, ticket.product_id, now);
+
+        emit(EventKind::RestakeInto(RestakeIntoData {
+            old_id: jar_id,
+            new_id: new_jar.id,
+            product_id: new_jar.product_id.clone(),
+        }));
+
+        new_jar.into()
+    }
+
+    fn set_auto_renew(&mut self, jar_id: JarIdView, auto_renew: bool) -> JarView {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let jar_id = jar_id.0;
+        let jar = self.get_jar_internal(&account_id, jar_id);
+        let product = self.get_product(&jar.product_id).clone();
+        let now = env::block_timestamp_ms();
+
+        assert_not_locked(&jar);
+        require!(product.allows_restaking(), "The product doesn't support restaking");
+
+        if auto_renew {
+            require!(product.is_enabled, "The product is disabled");
+            require!(
+                !jar.is_liquidable(&product, now),
+                "The jar is already mature, restake it instead"
+            );
+        }
+
+        if product.is_score_product() {
+            self.settle_score_interest(&account_id, now);
+        }
+
+        let jar = self.get_jar_mut_internal(&account_id, jar_id);
+        jar.set_auto_renew(auto_renew, &product, now);
+
+        let view: JarView = (&*jar).into();
+
+        emit(EventKind::SetAutoRenew(AutoRenewData { id: jar_id, auto_renew }));
+
+        view
+    }
+
     fn unlock_jars_for_account(&mut self, account_id: AccountId) {
         self.assert_account_is_not_migrating(&account_id);
         self.assert_manager();
@@ -230,7 +362,113 @@ impl JarApi for Contract {
         let jars = self.accounts.get_mut(&account_id).expect("Account doesn't have jars");
 
         for jar in &mut jars.jars {
-            jar.is_pending_withdraw = false;
+            jar.unlock();
+        }
+    }
+
+    fn get_stuck_locks(&self, account_id: AccountId) -> Vec<StuckLockView> {
+        let now = env::block_timestamp_ms();
+
+        self.account_jars(&account_id)
+            .iter()
+            .filter(|jar| jar.is_lock_stuck(now, self.locks_recorded_since))
+            .map(|jar| StuckLockView {
+                jar_id: U32(jar.id),
+                operation: jar.lock.map(|lock| lock.operation),
+                locked_at: jar.lock.map(|lock| lock.locked_at.into()),
+            })
+            .collect()
+    }
+
+    fn release_stuck_locks(&mut self) -> Vec<JarIdView> {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let now = env::block_timestamp_ms();
+        let locks_recorded_since = self.locks_recorded_since;
+
+        let Some(account) = self.accounts.get_mut(&account_id) else {
+            return vec![];
+        };
+
+        let mut released = vec![];
+
+        for jar in account
+            .jars
+            .iter_mut()
+            .filter(|jar| jar.is_lock_stuck(now, locks_recorded_since))
+        {
+            jar.unlock();
+            released.push(jar.id);
+        }
+
+        if !released.is_empty() {
+            emit(EventKind::ReleaseStuckLocks(released.clone()));
         }
+
+        released.into_iter().map(U32).collect()
+    }
+
+    fn set_jar_archive(&mut self, enabled: bool) {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let account = self
+            .accounts
+            .get_mut(&account_id)
+            .unwrap_or_else(|| env::panic_str("Account doesn't have jars"));
+
+        if enabled == account.closed_jars.is_some() {
+            return;
+        }
+
+        account.closed_jars = enabled.then(Vec::new);
+
+        emit(EventKind::SetJarArchive(enabled));
+    }
+
+    fn get_closed_jars(&self, account_id: AccountId) -> Vec<ClosedJarView> {
+        self.accounts
+            .get(&account_id)
+            .and_then(|account| account.closed_jars.as_ref())
+            .map_or(vec![], |closed_jars| {
+                closed_jars
+                    .iter()
+                    .map(|jar| ClosedJarView {
+                        id: U32(jar.id),
+                        product_id: jar.product_id.clone(),
+                        principal: U128(jar.principal),
+                        claimed_balance: U128(jar.claimed_balance),
+                        closed_at: U64(jar.closed_at),
+                    })
+                    .collect()
+            })
+    }
+
+    fn prune_closed_jars(&mut self, closed_before: Option<U64>) -> u32 {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let closed_jars = self
+            .accounts
+            .get_mut(&account_id)
+            .and_then(|account| account.closed_jars.as_mut())
+            .unwrap_or_else(|| env::panic_str("Archive of closed jars is off"));
+
+        let closed_before = closed_before.map_or(Timestamp::MAX, |timestamp| timestamp.0);
+
+        let (pruned, kept): (Vec<_>, Vec<_>) = closed_jars.drain(..).partition(|jar| jar.closed_at < closed_before);
+        *closed_jars = kept;
+
+        let pruned: Vec<JarId> = pruned.into_iter().map(|jar| jar.id).collect();
+
+        if !pruned.is_empty() {
+            emit(EventKind::PruneClosedJars(pruned.clone()));
+        }
+
+        pruned.len().try_into().expect("Archive size is bounded")
     }
 }
@@ -4,62 +4,53 @@ use ed25519_dalek::{Signature, VerifyingKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH
 use near_sdk::{
     env,
     env::{panic_str, sha256},
-    json_types::{Base64VecU8, U128, U64},
+    json_types::{Base64VecU8, U128},
     near, require, AccountId,
 };
 use sweat_jar_model::{
-    jar::{JarId, JarView},
-    ProductId, ScoreRecord, Timezone, TokenAmount, UDecimal, MS_IN_DAY, MS_IN_YEAR,
+    jar::{JarId, JarView, LockOperation},
+    ProductId, ScoreRecord, Timezone, ToAPY, TokenAmount, UDecimal, MS_IN_DAY, MS_IN_YEAR,
 };
 
 use crate::{
     common::Timestamp,
     event::{emit, EventKind, TopUpData},
-    jar::model::{Jar, JarLastVersion},
+    jar::model::{Jar, JarLastVersion, JarTicket},
     product::model::{Apy, Product, Terms},
     score::AccountScore,
     Contract, JarsStorage,
 };
 
-/// The `JarTicket` struct represents a request to create a deposit jar for a corresponding product.
-///
-/// The data from this `JarTicket` is later combined with additional data, including the contract
-/// account address, the recipient's account ID, the desired amount of tokens to deposit,
-/// and the ID of the last jar created for the recipient. The concatenation of this data
-/// forms a message that is then hashed using the SHA-256 algorithm. This resulting hash is used
-/// to verify the authenticity of the data against an Ed25519 signature provided in the `ft_transfer_call` data.
-#[derive(Clone, Debug)]
-#[near(serializers=[json])]
-pub struct JarTicket {
-    /// The unique identifier of the product for which the jar is intended to be created.
-    /// This `product_id` links the request to the specific terms and conditions of the product that will govern the behavior of the jar.
-    pub product_id: String,
-
-    /// Specifies the expiration date of the ticket. The expiration date is measured in milliseconds
-    /// since the Unix epoch. This property ensures that the request to create a jar is valid only
-    /// until the specified timestamp. After this timestamp, the ticket becomes
-    /// invalid and should not be accepted.
-    pub valid_until: U64,
-
-    /// An optional user timezone. Required for creating step jars.
-    pub timezone: Option<Timezone>,
-}
+/// Time after which a pending operation is considered failed and the owner is allowed to release the jar.
+/// Callbacks are resolved within a few blocks, so a day is far beyond any legitimate delay.
+pub(crate) const STUCK_LOCK_TIMEOUT: Timestamp = MS_IN_DAY;
 
 impl JarLastVersion {
-    pub(crate) fn lock(&mut self) {
+    pub(crate) fn lock(&mut self, operation: LockOperation, now: Timestamp) {
         self.is_pending_withdraw = true;
+        self.lock = Some(JarLock {
+            operation,
+            locked_at: now,
+        });
     }
 
     pub(crate) fn unlock(&mut self) {
         self.is_pending_withdraw = false;
+        self.lock = None;
     }
 
-    pub(crate) fn apply_penalty(&mut self, product: &Product, is_applied: bool, now: Timestamp) {
-        assert!(
-            !product.is_score_product(),
-            "Applying penalty is not supported for score based jars"
-        );
+    /// A lock is considered stuck if its callback should have been resolved long ago.
+    /// Jars locked before operations were recorded don't have a lock timestamp,
+    /// so their timeout is counted from `locks_recorded_since`.
+    pub(crate) fn is_lock_stuck(&self, now: Timestamp, locks_recorded_since: Timestamp) -> bool {
+        let locked_at = self.lock.map_or(locks_recorded_since, |lock| lock.locked_at);
 
+        self.is_pending_withdraw && now.saturating_sub(locked_at) >= STUCK_LOCK_TIMEOUT
+    }
+
+    /// For score based jars interest must be settled with `Contract::settle_score_interest` beforehand,
+    /// so the scores recorded before the penalty status change are credited with the previous status.
+    pub(crate) fn apply_penalty(&mut self, product: &Product, is_applied: bool, now: Timestamp) {
         let (interest, remainder) = self.get_interest(&ScoreRecord::default(), product, now);
 
         self.claim_remainder = remainder;
@@ -68,45 +59,122 @@ impl JarLastVersion {
             updated_at: now,
             interest,
         });
+        self.roll_over(product, now);
         self.is_penalty_applied = is_applied;
     }
 
+    /// For score based jars interest must be settled with `Contract::settle_score_interest` beforehand,
+    /// so the accrued interest is already in the cache and the new principal only affects following days.
     pub(crate) fn top_up(&mut self, amount: TokenAmount, product: &Product, now: Timestamp) -> &mut Self {
-        assert!(
-            !product.is_score_product(),
-            "Top up is not supported for score based jars"
-        );
-
         let current_interest = self.get_interest(&ScoreRecord::default(), product, now).0;
 
         self.principal += amount;
+        self.total_deposited += amount;
         self.cache = Some(JarCache {
             updated_at: now,
             interest: current_interest,
         });
+        self.roll_over(product, now);
+        self
+    }
+
+    /// Moves `amount` of principal to unbonding, so it doesn't accrue interest anymore.
+    /// A jar can have only one pending unbond, so it must not have one already.
+    /// For score based jars interest must be settled with `Contract::settle_score_interest` beforehand.
+    pub(crate) fn unbond(&mut self, amount: TokenAmount, product: &Product, now: Timestamp) -> &mut Self {
+        let unbonding_period = product
+            .unbonding_period
+            .unwrap_or_else(|| panic_str("The product doesn't have unbonding period"));
+
+        let (interest, remainder) = self.get_interest(&ScoreRecord::default(), product, now);
+
+        self.claim_remainder = remainder;
+        self.cache = Some(JarCache {
+            updated_at: now,
+            interest,
+        });
+
+        self.principal -= amount;
+        self.unbonding = Some(Unbonding {
+            amount,
+            unlock_at: now + unbonding_period,
+        });
         self
     }
 
-    pub(crate) fn claim(&mut self, claimed_amount: TokenAmount, now: Timestamp) -> &mut Self {
+    /// Returns unbonding tokens back to principal.
+    /// For score based jars interest must be settled with `Contract::settle_score_interest` beforehand.
+    pub(crate) fn cancel_unbond(&mut self, product: &Product, now: Timestamp) -> Unbonding {
+        let unbonding = self
+            .unbonding
+            .take()
+            .unwrap_or_else(|| panic_str("Jar doesn't have pending unbond"));
+
+        let (interest, remainder) = self.get_interest(&ScoreRecord::default(), product, now);
+
+        self.claim_remainder = remainder;
+        self.cache = Some(JarCache {
+            updated_at: now,
+            interest,
+        });
+
+        self.principal += unbonding.amount;
+
+        unbonding
+    }
+
+    pub(crate) fn claim(&mut self, claimed_amount: TokenAmount, product: &Product, now: Timestamp) -> &mut Self {
         self.claimed_balance += claimed_amount;
 
         self.cache = Some(JarCache {
             updated_at: now,
             interest: 0,
         });
+        self.roll_over(product, now);
         self
     }
 
+    /// For score based jars interest must be settled with `Contract::settle_score_interest` beforehand.
+    pub(crate) fn set_auto_renew(&mut self, auto_renew: bool, product: &Product, now: Timestamp) {
+        let (interest, remainder) = self.get_interest(&ScoreRecord::default(), product, now);
+
+        self.claim_remainder = remainder;
+        self.cache = Some(JarCache {
+            updated_at: now,
+            interest,
+        });
+        self.roll_over(product, now);
+        self.auto_renew = auto_renew;
+    }
+
+    /// Start of the current term of the jar. An auto-renewed Fixed jar rolls into a new term at maturity.
+    /// The renewal is computed here on read until it's materialized with `roll_over`.
+    pub(crate) fn term_start(&self, product: &Product, now: Timestamp) -> Timestamp {
+        match &product.terms {
+            Terms::Fixed(value) if self.auto_renew && value.lockup_term > 0 && now > self.term_started_at => {
+                let passed_terms = (now - self.term_started_at) / value.lockup_term;
+                self.term_started_at + passed_terms * value.lockup_term
+            }
+            _ => self.term_started_at,
+        }
+    }
+
+    /// Materializes auto-renewal of the jar. Interest must be cached at `now` beforehand,
+    /// otherwise interest accrued before the current term is lost.
+    fn roll_over(&mut self, product: &Product, now: Timestamp) {
+        self.term_started_at = self.term_start(product, now);
+    }
+
     pub(crate) fn should_be_closed(&self, score: &ScoreRecord, product: &Product, now: Timestamp) -> bool {
         !product.is_flexible() && self.principal == 0 && self.get_interest(score, product, now).0 == 0
     }
 
     /// Indicates whether a user can withdraw tokens from the jar at the moment or not.
     /// For a Flexible product withdrawal is always possible.
-    /// For Fixed product it's defined by the lockup term.
+    /// For Fixed product it's defined by the lockup term. Auto-renewed jars never mature.
     pub(crate) fn is_liquidable(&self, product: &Product, now: Timestamp) -> bool {
         match &product.terms {
-            Terms::Fixed(value) => now - self.created_at > value.lockup_term,
+            Terms::Fixed(value) => !self.auto_renew && now - self.term_started_at > value.lockup_term,
             Terms::Flexible => true,
         }
     }
@@ -164,17 +232,39 @@ impl JarLastVersion {
             return (cache, 0);
         }
 
-        if let Terms::Fixed(end_term) = &product.terms {
-            let end_term = cmp::max(now, self.created_at + end_term.lockup_term);
-            if now >= end_term {
-                return (cache, 0);
-            }
+        // Scores don't bring any interest while the penalty is applied. A reduced multiplier is left out
+        // on purpose: it would need a per-product setting, and zero is enough to stop gaming step data.
+        if self.is_penalty_applied {
+            return (cache, self.claim_remainder);
         }
 
-        let apy = product.apy_for_score(&score.score);
+        if !self.is_score_accruing(product, now) {
+            return (cache, 0);
+        }
+
+        let apy = product.apy_with_streak(product.apy_for_score(&score.score), score.streak);
         self.get_interest_for_term(cache, apy, MS_IN_DAY)
     }
 
+    /// Score based interest is accrued only until the end of a lockup term of a Fixed product,
+    /// unless the jar is auto-renewed.
+    fn is_score_accruing(&self, product: &Product, now: Timestamp) -> bool {
+        match &product.terms {
+            Terms::Fixed(value) => self.auto_renew || now < self.term_started_at + value.lockup_term,
+            Terms::Flexible => true,
+        }
+    }
+
+    /// Interest a score based jar receives for a day when the score reaches product's `score_cap`.
+    pub(crate) fn get_max_score_interest(&self, product: &Product, streak: u16, now: Timestamp) -> TokenAmount {
+        if self.is_penalty_applied || !self.is_score_accruing(product, now) {
+            return 0;
+        }
+
+        let apy = product.apy_with_streak(product.score_cap.to_apy(), streak);
+        self.get_interest_for_term(0, apy, MS_IN_DAY).0
+    }
+
     pub(crate) fn get_interest(&self, score: &ScoreRecord, product: &Product, now: Timestamp) -> (TokenAmount, u64) {
         if product.is_score_product() {
             self.get_score_interest(score, product, now)
@@ -198,7 +288,8 @@ impl JarLastVersion {
 
     fn get_interest_until_date(&self, product: &Product, now: Timestamp) -> Timestamp {
         match product.terms.clone() {
-            Terms::Fixed(value) => cmp::min(now, self.created_at + value.lockup_term),
+            Terms::Fixed(_) if self.auto_renew => now,
+            Terms::Fixed(value) => cmp::min(now, self.term_started_at + value.lockup_term),
             Terms::Flexible => now,
         }
     }
@@ -214,6 +305,23 @@ pub struct JarCache {
     pub interest: TokenAmount,
 }
 
+/// Tokens waiting for the end of product's unbonding period before they can be transferred to the user.
+#[near(serializers=[borsh, json])]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Unbonding {
+    pub amount: TokenAmount,
+    /// The timestamp after which the unbonding can be finalized.
+    pub unlock_at: Timestamp,
+}
+
+/// Describes the operation which locked the jar until its callback is resolved.
+#[near(serializers=[borsh, json])]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct JarLock {
+    pub operation: LockOperation,
+    pub locked_at: Timestamp,
+}
+
 impl Contract {
     pub(crate) fn create_jar(
         &mut self,
@@ -222,11 +330,25 @@ impl Contract {
         amount: U128,
         signature: Option<Base64VecU8>,
     ) -> JarView {
+        let jar = self.create_jar_internal(account_id, ticket, amount.0, signature);
+
+        emit(EventKind::CreateJar(jar.clone().into()));
+
+        jar.into()
+    }
+
+    /// Creates a jar without emitting an event, so the caller can describe the origin of the tokens.
+    pub(crate) fn create_jar_internal(
+        &mut self,
+        account_id: AccountId,
+        ticket: JarTicket,
+        amount: TokenAmount,
+        signature: Option<Base64VecU8>,
+    ) -> Jar {
         self.assert_account_is_not_migrating(&account_id);
 
-        let amount = amount.0;
         let product_id = &ticket.product_id;
-        let product = self.get_product(product_id);
+        let product = self.get_product(product_id).clone();
 
         product.assert_enabled();
         product.assert_cap(amount);
@@ -236,18 +358,7 @@ impl Contract {
         self.verify(&account_id, amount, &ticket, signature);
 
         if product.is_score_product() {
-            match (ticket.timezone, self.get_score_mut(&account_id)) {
-                // Time zone already set. No actions required.
-                (Some(_) | None, Some(_)) => (),
-                (Some(timezone), None) => {
-                    self.accounts.entry(account_id.clone()).or_default().score = AccountScore::new(timezone);
-                }
-                (None, None) => {
-                    panic_str(&format!(
-                        "Trying to create step base jar for account: '{account_id}' without providing time zone"
-                    ));
-                }
-            }
+            self.init_account_score(&account_id, ticket.timezone);
         }
 
         let id = self.increment_and_get_last_jar_id();
@@ -256,30 +367,53 @@ impl Contract {
 
         self.add_new_jar(&account_id, jar.clone());
 
-        emit(EventKind::CreateJar(jar.clone().into()));
+        jar
+    }
 
-        jar.into()
+    /// Step based jars require a time zone of the account to be set.
+    pub(crate) fn init_account_score(&mut self, account_id: &AccountId, timezone: Option<Timezone>) {
+        match (timezone, self.get_score_mut(account_id)) {
+            // Time zone already set. No actions required.
+            (Some(_) | None, Some(_)) => (),
+            (Some(timezone), None) => {
+                self.accounts.entry(account_id.clone()).or_default().score = AccountScore::new(timezone);
+            }
+            (None, None) => {
+                panic_str(&format!(
+                    "Trying to create step base jar for account: '{account_id}' without providing time zone"
+                ));
+            }
+        }
     }
 
     pub(crate) fn top_up(&mut self, account: &AccountId, jar_id: JarId, amount: U128) -> U128 {
+        let principal = self.top_up_internal(account, jar_id, amount.0).principal;
+
+        emit(EventKind::TopUp(TopUpData { id: jar_id, amount }));
+
+        U128(principal)
+    }
+
+    /// Tops up a jar without emitting an event, so the caller can describe the origin of the tokens.
+    pub(crate) fn top_up_internal(&mut self, account: &AccountId, jar_id: JarId, amount: TokenAmount) -> Jar {
         self.migrate_account_if_needed(account);
 
         let jar = self.get_jar_internal(account, jar_id).clone();
         let product = self.get_product(&jar.product_id).clone();
 
         require!(product.allows_top_up(), "The product doesn't allow top-ups");
-        product.assert_cap(jar.principal + amount.0);
+        product.assert_cap(jar.principal + amount);
 
         let now = env::block_timestamp_ms();
 
-        let principal = self
-            .get_jar_mut_internal(account, jar_id)
-            .top_up(amount.0, &product, now)
-            .principal;
+        if product.is_score_product() {
+            self.settle_score_interest(account, now);
+        }
 
-        emit(EventKind::TopUp(TopUpData { id: jar_id, amount }));
+        let jar = self.get_jar_mut_internal(account, jar_id);
+        jar.top_up(amount, &product, now);
 
-        U128(principal)
+        jar.clone()
     }
 
     pub(crate) fn delete_jar(&mut self, account_id: &AccountId, jar_id: JarId) {
@@ -298,7 +432,8 @@ impl Contract {
             .position(|j| j.id == jar_id)
             .unwrap_or_else(|| panic_str(&format!("Jar with id {jar_id} doesn't exist")));
 
-        jars.swap_remove(jar_position);
+        let jar = jars.swap_remove(jar_position);
+        jars.archive(&jar, env::block_timestamp_ms());
     }
 
     pub(crate) fn get_score(&self, account: &AccountId) -> Option<&AccountScore> {
@@ -36,6 +36,11 @@ impl From<JarLegacy> for Jar {
             is_pending_withdraw: value.is_pending_withdraw,
             is_penalty_applied: value.is_penalty_applied,
             claim_remainder: 0,
+            unbonding: None,
+            auto_renew: false,
+            lock: None,
+            total_deposited: value.principal,
+            term_started_at: value.created_at,
         }
         .into()
     }
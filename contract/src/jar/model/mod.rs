@@ -1,10 +1,16 @@
 mod common;
 mod legacy;
 mod v1;
+mod v2;
+mod v3;
+mod v4;
+mod v5;
+mod v6;
 mod versioned;
 
-pub use common::{JarCache, JarTicket};
+pub use common::{JarCache, JarLock, Unbonding};
 pub use legacy::*;
+pub use sweat_jar_model::jar::JarTicket;
 pub use versioned::Jar;
 
-pub type JarLastVersion = v1::JarV1;
+pub type JarLastVersion = v6::JarV6;
@@ -0,0 +1,78 @@
+use near_sdk::{near, AccountId};
+use sweat_jar_model::{jar::JarId, ProductId, TokenAmount};
+
+use crate::{
+    common::Timestamp,
+    jar::model::{v3::JarV3, JarCache, JarLock, Unbonding},
+};
+
+/// The `Jar` struct represents a deposit jar within the smart contract.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct JarV4 {
+    /// The unique identifier for the jar.
+    pub id: JarId,
+
+    /// The account ID of the owner of the jar.
+    pub account_id: AccountId,
+
+    /// The product ID that describes the terms of the deposit associated with the jar.
+    pub product_id: ProductId,
+
+    /// The timestamp of when the jar was created, measured in milliseconds since Unix epoch.
+    /// For auto-renewed jars it's the start of the current term once the renewal is materialized.
+    pub created_at: Timestamp,
+
+    /// The principal amount of the deposit stored in the jar.
+    pub principal: TokenAmount,
+
+    /// A cached value that stores calculated interest based on the current state of the jar.
+    /// This cache is updated whenever properties that impact interest calculation change,
+    /// allowing for efficient interest calculations between state changes.
+    pub cache: Option<JarCache>,
+
+    /// The amount of tokens that have been claimed from the jar up to the present moment.
+    pub claimed_balance: TokenAmount,
+
+    /// Indicates whether an operation involving cross-contract calls is in progress for this jar.
+    pub is_pending_withdraw: bool,
+
+    /// Indicates whether a penalty has been applied to the jar's owner due to violating product terms.
+    pub is_penalty_applied: bool,
+
+    /// Remainder of claim operation.
+    /// Needed to negate rounding error when user claims very often.
+    /// See `Jar::get_interest` method for implementation of this logic.
+    pub claim_remainder: u64,
+
+    /// Tokens requested for withdrawal from a product with unbonding period.
+    /// They are not a part of principal anymore and don't accrue interest.
+    pub unbonding: Option<Unbonding>,
+
+    /// Indicates whether a Fixed jar rolls into a new term at maturity instead of stopping accruing interest.
+    pub auto_renew: bool,
+
+    /// The operation which set `is_pending_withdraw` and the time it happened.
+    /// Allows the owner to release the jar if the callback of the operation has failed.
+    pub lock: Option<JarLock>,
+}
+
+impl From<JarV3> for JarV4 {
+    fn from(value: JarV3) -> Self {
+        Self {
+            id: value.id,
+            account_id: value.account_id,
+            product_id: value.product_id,
+            created_at: value.created_at,
+            principal: value.principal,
+            cache: value.cache,
+            claimed_balance: value.claimed_balance,
+            is_pending_withdraw: value.is_pending_withdraw,
+            is_penalty_applied: value.is_penalty_applied,
+            claim_remainder: value.claim_remainder,
+            unbonding: value.unbonding,
+            auto_renew: value.auto_renew,
+            lock: None,
+        }
+    }
+}
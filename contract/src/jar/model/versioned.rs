@@ -5,24 +5,29 @@ use near_sdk::{
         io::{Error, ErrorKind::InvalidData, Read},
         BorshDeserialize, BorshSerialize,
     },
-    serde::{Deserialize, Serialize},
+    serde::{Deserialize, Deserializer, Serialize},
     AccountId,
 };
 use sweat_jar_model::{jar::JarId, ProductId, ScoreRecord, TokenAmount};
 
 use crate::{
     common::Timestamp,
-    jar::model::{v1::JarV1, JarCache, JarLastVersion},
+    jar::model::{v1::JarV1, v2::JarV2, v3::JarV3, v4::JarV4, v5::JarV5, v6::JarV6, JarCache, JarLastVersion},
     product::model::Product,
 };
 
 pub type Jar = JarVersioned;
 
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, BorshSerialize, PartialEq)]
 #[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
 #[borsh(crate = "near_sdk::borsh")]
 pub enum JarVersioned {
     V1(JarV1),
+    V2(JarV2),
+    V3(JarV3),
+    V4(JarV4),
+    V5(JarV5),
+    V6(JarV6),
 }
 
 /// Custom `BorshDeserialize` implementation is needed to automatically
@@ -32,7 +37,17 @@ impl BorshDeserialize for JarVersioned {
         let tag: u8 = BorshDeserialize::deserialize_reader(reader)?;
 
         let result = match tag {
-            0 => JarVersioned::V1(BorshDeserialize::deserialize_reader(reader)?),
+            0 => JarVersioned::V6(
+                JarV5::from(JarV4::from(JarV3::from(JarV2::from(JarV1::deserialize_reader(
+                    reader,
+                )?))))
+                .into(),
+            ),
+            1 => JarVersioned::V6(JarV5::from(JarV4::from(JarV3::from(JarV2::deserialize_reader(reader)?))).into()),
+            2 => JarVersioned::V6(JarV5::from(JarV4::from(JarV3::deserialize_reader(reader)?)).into()),
+            3 => JarVersioned::V6(JarV5::from(JarV4::deserialize_reader(reader)?).into()),
+            4 => JarVersioned::V6(JarV5::deserialize_reader(reader)?.into()),
+            5 => JarVersioned::V6(BorshDeserialize::deserialize_reader(reader)?),
             // Add new versions here:
             _ => return Err(Error::new(InvalidData, format!("Unexpected variant tag: {tag:?}"))),
         };
@@ -41,6 +56,35 @@ impl BorshDeserialize for JarVersioned {
     }
 }
 
+/// Jars are passed to callbacks in JSON, so old versions are converted to latest version here too
+impl<'de> Deserialize<'de> for JarVersioned {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+        enum JarVersionedJson {
+            V1(JarV1),
+            V2(JarV2),
+            V3(JarV3),
+            V4(JarV4),
+            V5(JarV5),
+            V6(JarV6),
+        }
+
+        let result = match JarVersionedJson::deserialize(deserializer)? {
+            JarVersionedJson::V1(jar) => {
+                JarVersioned::V6(JarV5::from(JarV4::from(JarV3::from(JarV2::from(jar)))).into())
+            }
+            JarVersionedJson::V2(jar) => JarVersioned::V6(JarV5::from(JarV4::from(JarV3::from(jar))).into()),
+            JarVersionedJson::V3(jar) => JarVersioned::V6(JarV5::from(JarV4::from(jar)).into()),
+            JarVersionedJson::V4(jar) => JarVersioned::V6(JarV5::from(jar).into()),
+            JarVersionedJson::V5(jar) => JarVersioned::V6(jar.into()),
+            JarVersionedJson::V6(jar) => JarVersioned::V6(jar),
+        };
+
+        Ok(result)
+    }
+}
+
 impl JarVersioned {
     pub fn create(
         id: JarId,
@@ -60,6 +104,11 @@ impl JarVersioned {
             is_pending_withdraw: false,
             is_penalty_applied: false,
             claim_remainder: 0,
+            unbonding: None,
+            auto_renew: false,
+            lock: None,
+            total_deposited: principal,
+            term_started_at: created_at,
         }
         .into()
     }
@@ -75,6 +124,7 @@ impl JarVersioned {
     pub fn unlocked(&self) -> Self {
         JarLastVersion {
             is_pending_withdraw: false,
+            lock: None,
             ..self.deref().clone()
         }
         .into()
@@ -92,7 +142,7 @@ impl JarVersioned {
         withdrawn_amount: TokenAmount,
         now: Timestamp,
     ) -> Self {
-        JarV1 {
+        JarLastVersion {
             principal: self.principal - withdrawn_amount,
             cache: Some(JarCache {
                 updated_at: now,
@@ -108,9 +158,10 @@ impl Deref for JarVersioned {
     type Target = JarLastVersion;
     fn deref(&self) -> &Self::Target {
         match self {
-            Self::V1(jar) => jar,
-            // Guaranteed by `BorshDeserialize` implementation
-            // Self::V2(jar) => jar, <- Add new version here
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) | Self::V5(_) => {
+                unreachable!("Guaranteed by `BorshDeserialize` implementation")
+            }
+            Self::V6(jar) => jar,
         }
     }
 }
@@ -118,15 +169,16 @@ impl Deref for JarVersioned {
 impl DerefMut for JarVersioned {
     fn deref_mut(&mut self) -> &mut Self::Target {
         match self {
-            Self::V1(jar) => jar,
-            // Guaranteed by `BorshDeserialize` implementation
-            // Self::V2(jar) => jar, <- Add new version here
+            Self::V1(_) | Self::V2(_) | Self::V3(_) | Self::V4(_) | Self::V5(_) => {
+                unreachable!("Guaranteed by `BorshDeserialize` implementation")
+            }
+            Self::V6(jar) => jar,
         }
     }
 }
 
-impl From<JarV1> for JarVersioned {
-    fn from(value: JarV1) -> Self {
-        Self::V1(value)
+impl From<JarV6> for JarVersioned {
+    fn from(value: JarV6) -> Self {
+        Self::V6(value)
     }
 }
@@ -0,0 +1,128 @@
+use near_sdk::{
+    json_types::{U128, U64},
+    test_utils::test_env::alice,
+};
+use sweat_jar_model::{
+    api::{ClaimApi, JarApi, WithdrawApi},
+    jar::ClosedJarView,
+    UDecimal, U32,
+};
+
+use crate::{
+    common::tests::Context,
+    jar::{account::v5::MAX_CLOSED_JARS, model::Jar},
+    product::model::{Apy, Product},
+    test_utils::{admin, expect_panic, UnwrapPromise},
+};
+
+fn prepare_context(jars_count: u32) -> (Context, Product) {
+    let product = Product::new().apy(Apy::Constant(UDecimal::new(2, 1)));
+    let jars: Vec<_> = (0..jars_count).map(|id| Jar::new(id).product_id(&product.id)).collect();
+
+    let mut context = Context::new(admin()).with_products(&[product.clone()]).with_jars(&jars);
+
+    context.switch_account(alice());
+
+    (context, product)
+}
+
+#[test]
+fn closed_jar_is_recorded_in_archive() {
+    let (mut context, product) = prepare_context(1);
+
+    context.contract().set_jar_archive(true);
+
+    let now = product.get_lockup_term().unwrap() + 1;
+    context.set_block_timestamp_in_ms(now);
+
+    context.contract().withdraw(U32(0), None).unwrap();
+    assert!(context.contract().get_closed_jars(alice()).is_empty());
+
+    context.contract().claim_total(None).unwrap();
+
+    assert_eq!(
+        context.contract().get_closed_jars(alice()),
+        vec![ClosedJarView {
+            id: U32(0),
+            product_id: product.id,
+            principal: U128(1_000_000),
+            claimed_balance: U128(200_000),
+            closed_at: U64(now),
+        }]
+    );
+}
+
+#[test]
+fn closed_jar_is_not_recorded_when_archive_is_off() {
+    let (mut context, product) = prepare_context(1);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+
+    context.contract().claim_total(None).unwrap();
+    context.contract().withdraw(U32(0), None).unwrap();
+
+    assert!(context.contract().get_closed_jars(alice()).is_empty());
+}
+
+#[test]
+fn archive_drops_oldest_records_when_full() {
+    let jars_count = u32::try_from(MAX_CLOSED_JARS).unwrap() + 2;
+    let (context, _) = prepare_context(jars_count);
+
+    context.contract().set_jar_archive(true);
+
+    for id in 0..jars_count {
+        context.contract().delete_jar(&alice(), id);
+    }
+
+    let closed_jars = context.contract().get_closed_jars(alice());
+
+    assert_eq!(closed_jars.len(), MAX_CLOSED_JARS);
+    assert_eq!(closed_jars.first().unwrap().id, U32(2));
+    assert_eq!(closed_jars.last().unwrap().id, U32(jars_count - 1));
+}
+
+#[test]
+fn prune_closed_jars() {
+    let (mut context, _) = prepare_context(3);
+
+    context.contract().set_jar_archive(true);
+
+    for id in 0..3 {
+        context.set_block_timestamp_in_ms(u64::from(id) * 100);
+        context.contract().delete_jar(&alice(), id);
+    }
+
+    assert_eq!(context.contract().prune_closed_jars(Some(U64(150))), 2);
+    assert_eq!(
+        context
+            .contract()
+            .get_closed_jars(alice())
+            .into_iter()
+            .map(|jar| jar.id)
+            .collect::<Vec<_>>(),
+        vec![U32(2)]
+    );
+
+    assert_eq!(context.contract().prune_closed_jars(None), 1);
+    assert!(context.contract().get_closed_jars(alice()).is_empty());
+}
+
+#[test]
+fn disable_archive_removes_records() {
+    let (context, _) = prepare_context(2);
+
+    context.contract().set_jar_archive(true);
+    context.contract().delete_jar(&alice(), 0);
+    assert_eq!(context.contract().get_closed_jars(alice()).len(), 1);
+
+    context.contract().set_jar_archive(false);
+    assert!(context.contract().get_closed_jars(alice()).is_empty());
+
+    context.contract().delete_jar(&alice(), 1);
+    assert!(context.contract().get_closed_jars(alice()).is_empty());
+
+    expect_panic(&context, "Archive of closed jars is off", || {
+        context.contract().prune_closed_jars(None);
+    });
+}
@@ -0,0 +1,92 @@
+use near_sdk::test_utils::test_env::alice;
+use sweat_jar_model::{
+    api::{ClaimApi, JarApi, WithdrawApi},
+    MS_IN_DAY, MS_IN_YEAR, U32,
+};
+
+use crate::{
+    common::tests::Context,
+    jar::model::Jar,
+    product::model::Product,
+    test_utils::{admin, expect_panic, UnwrapPromise, PRINCIPAL},
+};
+
+fn prepare_context(allows_restaking: bool) -> Context {
+    let product = Product::new().with_allows_restaking(allows_restaking);
+
+    let mut context = Context::new(admin())
+        .with_products(&[product])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(alice());
+
+    context
+}
+
+#[test]
+fn auto_renewed_jar_keeps_accruing_interest() {
+    let mut context = prepare_context(true);
+
+    context.set_block_timestamp_in_days(1);
+    let jar = context.contract().set_auto_renew(U32(0), true);
+    assert!(jar.auto_renew);
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR * 5 / 2);
+
+    assert_eq!(context.contract().get_total_interest(alice()).amount.total.0, 300_000);
+
+    // Renewal is computed on read and is not stored until the jar is changed
+    assert_eq!(context.contract().get_jar_internal(&alice(), 0).term_started_at, 0);
+
+    expect_panic(&context, "The jar is not mature yet", || {
+        context.contract().withdraw(U32(0), None);
+    });
+
+    context.contract().claim_total(None).unwrap();
+
+    let jar = context.contract().get_jar_internal(&alice(), 0);
+    assert_eq!(jar.term_started_at, MS_IN_YEAR * 2);
+    // Creation time isn't affected by renewals
+    assert_eq!(jar.created_at, 0);
+}
+
+#[test]
+fn turn_off_auto_renew() {
+    let mut context = prepare_context(true);
+
+    context.contract().set_auto_renew(U32(0), true);
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR * 3 / 2);
+    let jar = context.contract().set_auto_renew(U32(0), false);
+
+    assert!(!jar.auto_renew);
+    assert_eq!(jar.created_at.0, 0);
+    assert_eq!(
+        context.contract().get_jar_internal(&alice(), 0).term_started_at,
+        MS_IN_YEAR
+    );
+
+    // The jar matures at the end of the current term
+    context.set_block_timestamp_in_ms(MS_IN_YEAR * 3);
+    assert_eq!(context.contract().get_total_interest(alice()).amount.total.0, 240_000);
+
+    let withdrawn = context.contract().withdraw(U32(0), None).unwrap();
+    assert_eq!(withdrawn.withdrawn_amount.0, PRINCIPAL);
+}
+
+#[test]
+fn auto_renew_restrictions() {
+    let context = prepare_context(false);
+
+    expect_panic(&context, "The product doesn't support restaking", || {
+        context.contract().set_auto_renew(U32(0), true);
+    });
+
+    let mut context = prepare_context(true);
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR + MS_IN_DAY);
+
+    expect_panic(&context, "The jar is already mature, restake it instead", || {
+        context.contract().set_auto_renew(U32(0), true);
+    });
+}
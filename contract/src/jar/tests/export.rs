@@ -0,0 +1,57 @@
+use near_sdk::{json_types::U128, test_utils::test_env::alice};
+use sweat_jar_model::{
+    api::{JarApi, ProductApi},
+    export::ACCOUNT_EXPORT_VERSION,
+    MS_IN_YEAR, U32,
+};
+
+use crate::{
+    common::tests::Context,
+    jar::model::Jar,
+    product::model::Product,
+    test_utils::{admin, PRINCIPAL},
+};
+
+#[test]
+fn export_account() {
+    let fixed = Product::new().id("fixed");
+    let flexible = Product::new().id("flexible").flexible();
+
+    let mut context = Context::new(admin())
+        .with_products(&[fixed.clone(), flexible])
+        .with_jars(&[
+            Jar::new(1).product_id("flexible").principal(PRINCIPAL),
+            Jar::new(0).product_id("fixed").principal(PRINCIPAL),
+        ]);
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR / 2);
+
+    let export = context.contract().export_account(alice());
+
+    assert_eq!(export.version, ACCOUNT_EXPORT_VERSION);
+    assert_eq!(export.account_id, alice());
+    assert_eq!(export.timestamp.0, MS_IN_YEAR / 2);
+    assert!(export.score.is_none());
+
+    assert_eq!(
+        export.jars.iter().map(|jar| jar.id).collect::<Vec<_>>(),
+        vec![U32(0), U32(1)]
+    );
+    assert_eq!(export.total_principal, U128(2 * PRINCIPAL));
+    assert_eq!(
+        export.total_accrued_interest,
+        context.contract().get_total_interest(alice()).amount.total
+    );
+
+    let fixed_jar = &export.jars[0];
+    assert_eq!(fixed_jar.matures_at.unwrap().0, MS_IN_YEAR);
+    assert_eq!(fixed_jar.accrued_interest, U128(60_000));
+    assert_eq!(fixed_jar.product_terms_hash, fixed.terms_hash());
+    assert!(export.jars[1].matures_at.is_none());
+
+    // Disabling the product doesn't change its terms
+    context.switch_account(admin());
+    context.contract().set_enabled(fixed.id.clone(), false);
+
+    assert_eq!(context.contract().export_account(alice()), export);
+}
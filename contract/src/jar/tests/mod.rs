@@ -1,5 +1,9 @@
 #![cfg(test)]
 
+mod archive;
+mod auto_renew;
+mod export;
 mod restake;
 mod restake_all;
+mod stuck_locks;
 mod tests;
@@ -1,14 +1,19 @@
-use near_sdk::test_utils::test_env::{alice, bob, carol};
+use near_sdk::{
+    json_types::{Base64VecU8, U64},
+    test_utils::test_env::{alice, bob, carol},
+};
 use sweat_jar_model::{
     api::{JarApi, ProductApi},
+    jar::JarTicket,
     U32,
 };
 
 use crate::{
     common::tests::Context,
     jar::model::Jar,
-    product::model::Product,
-    test_utils::{admin, expect_panic},
+    product::{helpers::MessageSigner, model::Product},
+    test_utils::{admin, expect_panic, generate_premium_product, PRINCIPAL},
+    Contract,
 };
 
 #[test]
@@ -84,8 +89,6 @@ fn restake_with_disabled_product() {
     context.switch_account(&admin);
     context.with_deposit_yocto(1, |context| context.contract().set_enabled(product.id, false));
 
-    context.contract().products_cache.borrow_mut().clear();
-
     context.set_block_timestamp_in_days(366);
 
     context.switch_account(&alice);
@@ -190,3 +193,111 @@ fn restake_all_while_migrating() {
     context.switch_account(&alice);
     let _ = context.contract().restake_all(None);
 }
+
+fn successor_ticket(product_id: &str) -> JarTicket {
+    JarTicket {
+        product_id: product_id.to_string(),
+        valid_until: U64(u64::MAX),
+        timezone: None,
+    }
+}
+
+#[test]
+fn restake_into_successor_product() {
+    let alice = alice();
+
+    let product = Product::new().with_allows_restaking(false);
+    let successor = Product::new().id("successor").cap(0, 10 * PRINCIPAL);
+    let jar = Jar::new(0);
+    let mut context = Context::new(admin())
+        .with_products(&[product, successor.clone()])
+        .with_jars(&[jar.clone()]);
+
+    context.switch_account(&alice);
+
+    expect_panic(&context, "The jar is not mature yet", || {
+        context
+            .contract()
+            .restake_into(U32(jar.id), successor_ticket(&successor.id), None);
+    });
+
+    context.set_block_timestamp_in_days(366);
+
+    let restaked = context
+        .contract()
+        .restake_into(U32(jar.id), successor_ticket(&successor.id), None);
+
+    assert_eq!(restaked.product_id, successor.id);
+    assert_eq!(restaked.principal.0, PRINCIPAL);
+
+    assert_eq!(context.contract().get_jar(alice, U32(jar.id)).principal.0, 0);
+}
+
+#[test]
+fn restake_into_product_with_insufficient_cap() {
+    let alice = alice();
+
+    let successor = Product::new().id("successor").cap(0, PRINCIPAL / 2);
+    let disabled = Product::new().id("disabled").enabled(false);
+    let jar = Jar::new(0);
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new(), successor.clone(), disabled.clone()])
+        .with_jars(&[jar.clone()]);
+
+    context.set_block_timestamp_in_days(366);
+    context.switch_account(&alice);
+
+    expect_panic(&context, "Total amount is out of product bounds", || {
+        context
+            .contract()
+            .restake_into(U32(jar.id), successor_ticket(&successor.id), None);
+    });
+
+    expect_panic(
+        &context,
+        "It's not possible to create new jars for this product",
+        || {
+            context
+                .contract()
+                .restake_into(U32(jar.id), successor_ticket(&disabled.id), None);
+        },
+    );
+}
+
+#[test]
+fn restake_into_premium_product() {
+    let alice = alice();
+
+    let signer = MessageSigner::new();
+    let successor = generate_premium_product("premium_successor", &signer);
+    let jar = Jar::new(0);
+    let mut context = Context::new(admin())
+        .with_products(&[Product::new(), successor.clone()])
+        .with_jars(&[jar.clone()]);
+
+    context.set_block_timestamp_in_days(366);
+    context.switch_account(&alice);
+
+    let ticket = successor_ticket(&successor.id);
+
+    expect_panic(&context, "Signature is required", || {
+        context.contract().restake_into(U32(jar.id), ticket.clone(), None);
+    });
+
+    let last_jar_id = context.contract().accounts.get(&alice).map(|account| account.last_id);
+    let signature = signer.sign(&Contract::get_signature_material(
+        &context.owner,
+        &alice,
+        &ticket.product_id,
+        PRINCIPAL,
+        last_jar_id,
+        ticket.valid_until.0,
+    ));
+
+    let restaked = context
+        .contract()
+        .restake_into(U32(jar.id), ticket, Some(Base64VecU8(signature)));
+
+    assert_eq!(restaked.product_id, successor.id);
+    assert_eq!(restaked.principal.0, PRINCIPAL);
+}
@@ -0,0 +1,107 @@
+use near_sdk::test_utils::test_env::{alice, bob};
+use sweat_jar_model::{
+    api::JarApi,
+    jar::{LockOperation, StuckLockView},
+    MS_IN_DAY, U32,
+};
+
+use crate::{common::tests::Context, jar::model::Jar, product::model::Product, test_utils::admin};
+
+fn prepare_context() -> Context {
+    let product = Product::new();
+
+    let mut locked_jar = Jar::new(0).product_id(&product.id).principal(100);
+    locked_jar.lock(LockOperation::Withdraw, MS_IN_DAY);
+
+    let legacy_locked_jar = Jar::new(1).product_id(&product.id).principal(100).pending_withdraw();
+    let jar = Jar::new(2).product_id(&product.id).principal(100);
+
+    Context::new(admin())
+        .with_products(&[product])
+        .with_jars(&[locked_jar, legacy_locked_jar, jar])
+}
+
+#[test]
+fn get_stuck_locks() {
+    let mut context = prepare_context();
+
+    context.set_block_timestamp_in_ms(MS_IN_DAY + 1);
+
+    assert_eq!(
+        context.contract().get_stuck_locks(alice()),
+        vec![StuckLockView {
+            jar_id: U32(1),
+            operation: None,
+            locked_at: None,
+        }]
+    );
+
+    context.set_block_timestamp_in_ms(2 * MS_IN_DAY);
+
+    assert_eq!(
+        context.contract().get_stuck_locks(alice()),
+        vec![
+            StuckLockView {
+                jar_id: U32(0),
+                operation: Some(LockOperation::Withdraw),
+                locked_at: Some(MS_IN_DAY.into()),
+            },
+            StuckLockView {
+                jar_id: U32(1),
+                operation: None,
+                locked_at: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn legacy_lock_timeout_is_counted_since_locks_are_recorded() {
+    let mut context = prepare_context();
+
+    context.contract().locks_recorded_since = MS_IN_DAY;
+
+    context.set_block_timestamp_in_ms(MS_IN_DAY + 1);
+
+    assert!(context.contract().get_stuck_locks(alice()).is_empty());
+
+    context.set_block_timestamp_in_ms(2 * MS_IN_DAY);
+
+    let stuck: Vec<_> = context
+        .contract()
+        .get_stuck_locks(alice())
+        .into_iter()
+        .map(|lock| lock.jar_id)
+        .collect();
+
+    assert_eq!(stuck, vec![U32(0), U32(1)]);
+}
+
+#[test]
+fn release_stuck_locks_by_owner() {
+    let mut context = prepare_context();
+
+    context.switch_account(alice());
+    context.set_block_timestamp_in_ms(MS_IN_DAY + 1);
+
+    assert_eq!(context.contract().release_stuck_locks(), vec![U32(1)]);
+    assert!(context.contract().get_jar(alice(), U32(0)).is_pending_withdraw);
+    assert!(!context.contract().get_jar(alice(), U32(1)).is_pending_withdraw);
+
+    context.set_block_timestamp_in_ms(2 * MS_IN_DAY);
+
+    assert_eq!(context.contract().release_stuck_locks(), vec![U32(0)]);
+    assert!(!context.contract().get_jar(alice(), U32(0)).is_pending_withdraw);
+    assert!(context.contract().get_stuck_locks(alice()).is_empty());
+}
+
+#[test]
+fn release_stuck_locks_of_another_account() {
+    let mut context = prepare_context();
+
+    context.switch_account(bob());
+    context.set_block_timestamp_in_ms(2 * MS_IN_DAY);
+
+    assert!(context.contract().release_stuck_locks().is_empty());
+    assert_eq!(context.contract().get_stuck_locks(alice()).len(), 2);
+}
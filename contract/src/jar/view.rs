@@ -1,7 +1,7 @@
 use near_sdk::json_types::{U128, U64};
-use sweat_jar_model::{jar::JarView, U32};
+use sweat_jar_model::{export::JarExportView, jar::JarView, ScoreRecord, U32};
 
-use crate::jar::model::Jar;
+use crate::{common::Timestamp, jar::model::Jar, product::model::Product};
 
 impl From<Jar> for JarView {
     fn from(value: Jar) -> Self {
@@ -14,6 +14,7 @@ impl From<Jar> for JarView {
             claimed_balance: U128(value.claimed_balance),
             is_penalty_applied: value.is_penalty_applied,
             is_pending_withdraw: value.is_pending_withdraw,
+            auto_renew: value.auto_renew,
         }
     }
 }
@@ -29,6 +30,30 @@ impl From<&Jar> for JarView {
             claimed_balance: U128(value.claimed_balance),
             is_penalty_applied: value.is_penalty_applied,
             is_pending_withdraw: value.is_pending_withdraw,
+            auto_renew: value.auto_renew,
+        }
+    }
+}
+
+impl Jar {
+    pub(crate) fn export(&self, product: &Product, score: &ScoreRecord, now: Timestamp) -> JarExportView {
+        JarExportView {
+            id: U32(self.id),
+            product_id: self.product_id.clone(),
+            product_terms_hash: product.terms_hash(),
+            created_at: U64(self.created_at),
+            principal: U128(self.principal),
+            cached_interest: U128(self.cache.map(|cache| cache.interest).unwrap_or_default()),
+            cache_updated_at: self.cache.map(|cache| U64(cache.updated_at)),
+            claim_remainder: U64(self.claim_remainder),
+            claimed_balance: U128(self.claimed_balance),
+            accrued_interest: U128(self.get_interest(score, product, now).0),
+            matures_at: product
+                .get_lockup_term()
+                .map(|term| U64(self.term_start(product, now) + term)),
+            is_pending_withdraw: self.is_pending_withdraw,
+            is_penalty_applied: self.is_penalty_applied,
+            auto_renew: self.auto_renew,
         }
     }
 }
@@ -1,23 +1,28 @@
-use std::{cell::RefCell, collections::HashMap};
-
 use near_sdk::{
-    collections::UnorderedMap,
     env,
     json_types::Base64VecU8,
     near, near_bindgen,
-    store::{LookupMap, LookupSet},
+    store::{IterableMap, LookupMap, LookupSet},
     AccountId, BorshStorageKey, PanicOnDefault,
 };
-use near_self_update_proc::SelfUpdate;
-use product::model::{Apy, Product};
+use product::{
+    model::{Apy, Product},
+    versioned::ProductVersioned,
+};
 use sweat_jar_model::{api::InitApi, jar::JarId, ProductId};
 
 use crate::{
+    common::Timestamp,
     jar::{
         account::versioned::Account,
         model::{AccountJarsLegacy, Jar},
     },
-    migration::account_jars_non_versioned::AccountJarsNonVersioned,
+    migration::{
+        account_jars_non_versioned::AccountJarsNonVersioned,
+        receipts::MigrationReceipt,
+        registry::{write_state_version, CURRENT_STATE_VERSION},
+    },
+    upgrade::StagedUpgrade,
 };
 
 mod assert;
@@ -36,13 +41,15 @@ mod score;
 mod test_builder;
 mod test_utils;
 mod tests;
+mod unbond;
+mod upgrade;
 mod withdraw;
 
 pub const PACKAGE_NAME: &str = env!("CARGO_PKG_NAME");
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[near(contract_state)]
-#[derive(PanicOnDefault, SelfUpdate)]
+#[derive(PanicOnDefault)]
 /// The `Contract` struct represents the state of the smart contract managing fungible token deposit jars.
 pub struct Contract {
     /// The account ID of the fungible token contract (NEP-141) that this jars contract interacts with.
@@ -55,7 +62,7 @@ pub struct Contract {
     pub manager: AccountId,
 
     /// A collection of products, each representing terms for specific deposit jars.
-    pub products: UnorderedMap<ProductId, Product>,
+    pub products: IterableMap<ProductId, ProductVersioned>,
 
     /// The last jar ID. Is used as nonce in `get_ticket_hash` method.
     pub last_jar_id: JarId,
@@ -66,12 +73,25 @@ pub struct Contract {
     pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
     pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
 
-    /// Cache to make access to products faster
-    /// Is not stored in contract state so it should be always skipped by borsh
-    #[borsh(skip)]
-    pub products_cache: RefCell<HashMap<ProductId, Product>>,
-
     pub migration: MigrationState,
+
+    /// Progress of moving accounts out of `account_jars_v1` and `account_jars_non_versioned`.
+    pub legacy_accounts: LegacyAccountsMigration,
+
+    /// Receipts of accounts migrated to v2 contract.
+    pub migration_receipts: LookupMap<AccountId, MigrationReceipt>,
+
+    /// Upgrade of the contract code waiting for its timelock to expire.
+    pub staged_upgrade: Option<StagedUpgrade>,
+
+    /// Products acknowledged by v2 contract.
+    pub migrated_products: LookupSet<ProductId>,
+
+    /// Time since which jar locks are recorded. Jars locked before it don't have a lock timestamp.
+    pub locks_recorded_since: Timestamp,
+
+    /// Products sent to v2 contract and waiting for its response.
+    pub migrating_products: LookupSet<ProductId>,
 }
 
 #[near]
@@ -80,6 +100,21 @@ pub struct MigrationState {
     pub migrating_accounts: LookupSet<AccountId>,
 }
 
+#[near]
+#[derive(Default)]
+pub struct LegacyAccountsMigration {
+    /// Number of accounts left in legacy storages.
+    /// It's `None` until the manager sets it and decreases with each migrated account.
+    pub remaining: Option<u32>,
+
+    /// Number of accounts moved out of legacy storages since the counter was introduced.
+    pub migrated: u32,
+
+    /// Batched migration is closed once it's complete. The remaining count is provided by the manager,
+    /// so legacy storages are still consulted in case it was wrong and some accounts were left behind.
+    pub is_complete: bool,
+}
+
 #[near]
 #[derive(BorshStorageKey)]
 pub(crate) enum StorageKey {
@@ -90,10 +125,23 @@ pub(crate) enum StorageKey {
     /// Products migrated to near_sdk 5
     _ProductsLegacyV2,
     /// Products migrated to step jars
-    Products,
+    _ProductsLegacyV3,
     Accounts,
     _SkippedKey, // This was used in one of the migrations, but is not needed anymore
     Migration,
+    /// Products with streak bonuses
+    _ProductsLegacyV4,
+    /// Products with unbonding period
+    _ProductsLegacyV5,
+    /// Products with fee split
+    _ProductsLegacyV6,
+    MigrationReceipts,
+    MigratedProducts,
+    /// Products with version tag
+    _ProductsLegacyV7,
+    /// Products in iterable map
+    Products,
+    MigratingProducts,
 }
 
 #[near_bindgen]
@@ -106,20 +154,30 @@ impl InitApi for Contract {
         manager: AccountId,
         new_version_account_id: AccountId,
     ) -> Self {
+        write_state_version(CURRENT_STATE_VERSION);
+
         Self {
             token_account_id,
             fee_account_id,
             manager,
-            products: UnorderedMap::new(StorageKey::_ProductsLegacyV2),
+            products: IterableMap::new(StorageKey::Products),
             account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
             account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
             last_jar_id: 0,
             accounts: LookupMap::new(StorageKey::Accounts),
-            products_cache: HashMap::default().into(),
             migration: MigrationState {
                 new_version_account_id,
                 migrating_accounts: LookupSet::new(StorageKey::Migration),
             },
+            legacy_accounts: LegacyAccountsMigration {
+                is_complete: true,
+                ..LegacyAccountsMigration::default()
+            },
+            migration_receipts: LookupMap::new(StorageKey::MigrationReceipts),
+            staged_upgrade: None,
+            migrated_products: LookupSet::new(StorageKey::MigratedProducts),
+            locks_recorded_since: env::block_timestamp_ms(),
+            migrating_products: LookupSet::new(StorageKey::MigratingProducts),
         }
     }
 }
@@ -129,6 +187,18 @@ pub(crate) trait JarsStorage<J> {
     fn get_jar_mut(&mut self, id: JarId) -> &mut J;
 }
 
+/// Products are deserialized once per call and cached by `IterableMap`.
+pub(crate) trait ProductsStorage {
+    fn get_product(&self, id: &ProductId) -> &Product;
+}
+
+impl ProductsStorage for IterableMap<ProductId, ProductVersioned> {
+    fn get_product(&self, id: &ProductId) -> &Product {
+        self.get(id)
+            .unwrap_or_else(|| env::panic_str(&format!("Product '{id}' doesn't exist")))
+    }
+}
+
 impl JarsStorage<Jar> for Vec<Jar> {
     fn get_jar(&self, id: JarId) -> &Jar {
         self.iter()
@@ -64,6 +64,11 @@ impl Contract {
                 is_pending_withdraw: false,
                 is_penalty_applied: false,
                 claim_remainder: 0,
+                unbonding: None,
+                auto_renew: false,
+                lock: None,
+                total_deposited: ce_fi_jar.principal.0,
+                term_started_at: ce_fi_jar.created_at.0,
             };
 
             total_amount += jar.principal;
@@ -3,7 +3,7 @@
 use near_sdk::{near_bindgen, AccountId};
 use sweat_jar_model::api::MigrationToClaimRemainder;
 
-use crate::{Contract, ContractExt};
+use crate::{jar::account::versioned::Account, Contract, ContractExt};
 
 #[near_bindgen]
 impl MigrationToClaimRemainder for Contract {
@@ -19,11 +19,16 @@ impl Contract {
     /// Dynamic jars migration method
     #[mutants::skip]
     pub fn migrate_account_if_needed(&mut self, account_id: &AccountId) {
-        if let Some(jars) = self.account_jars_v1.remove(account_id) {
-            self.accounts.insert(account_id.clone(), jars.into());
+        let account: Account = if let Some(jars) = self.account_jars_v1.remove(account_id) {
+            jars.into()
         } else if let Some(jars) = self.account_jars_non_versioned.remove(account_id) {
-            self.accounts.insert(account_id.clone(), jars.into());
+            jars.into()
+        } else {
+            return;
         };
+
+        self.accounts.insert(account_id.clone(), account);
+        self.legacy_accounts.on_account_migrated();
     }
 }
 
@@ -45,6 +50,7 @@ mod test {
     fn account_jars_legacy_migration() {
         let ctx = Context::new(admin());
         let mut contract = ctx.contract();
+        contract.legacy_accounts.is_complete = false;
 
         contract.account_jars_v1.insert(
             alice(),
@@ -71,9 +77,9 @@ mod test {
 
         assert_eq!(
             contract.accounts.get(&alice()).unwrap(),
-            &Account::V1(AccountJarsLastVersion {
+            &Account::V5(AccountJarsLastVersion {
                 last_id: 5,
-                jars: vec![Jar::V1(JarLastVersion {
+                jars: vec![Jar::V6(JarLastVersion {
                     id: 5,
                     account_id: alice(),
                     product_id: "product".to_string(),
@@ -87,8 +93,15 @@ mod test {
                     is_pending_withdraw: true,
                     is_penalty_applied: true,
                     claim_remainder: 0,
+                    unbonding: None,
+                    auto_renew: false,
+                    lock: None,
+                    total_deposited: 6,
+                    term_started_at: 5,
                 })],
                 score: Default::default(),
+                claim_operators: vec![],
+                closed_jars: None,
             })
         )
     }
@@ -97,12 +110,13 @@ mod test {
     fn account_jars_non_versioned_migration() {
         let ctx = Context::new(admin());
         let mut contract = ctx.contract();
+        contract.legacy_accounts.is_complete = false;
 
         contract.account_jars_non_versioned.insert(
             alice(),
             AccountJarsNonVersioned {
                 last_id: 5,
-                jars: vec![Jar::V1(JarLastVersion {
+                jars: vec![Jar::V6(JarLastVersion {
                     id: 5,
                     account_id: alice(),
                     product_id: "product".to_string(),
@@ -116,6 +130,11 @@ mod test {
                     is_pending_withdraw: true,
                     is_penalty_applied: true,
                     claim_remainder: 0,
+                    unbonding: None,
+                    auto_renew: false,
+                    lock: None,
+                    total_deposited: 6,
+                    term_started_at: 5,
                 })],
             },
         );
@@ -124,9 +143,9 @@ mod test {
 
         assert_eq!(
             contract.accounts.get(&alice()).unwrap(),
-            &Account::V1(AccountJarsLastVersion {
+            &Account::V5(AccountJarsLastVersion {
                 last_id: 5,
-                jars: vec![Jar::V1(JarLastVersion {
+                jars: vec![Jar::V6(JarLastVersion {
                     id: 5,
                     account_id: alice(),
                     product_id: "product".to_string(),
@@ -140,8 +159,15 @@ mod test {
                     is_pending_withdraw: true,
                     is_penalty_applied: true,
                     claim_remainder: 0,
+                    unbonding: None,
+                    auto_renew: false,
+                    lock: None,
+                    total_deposited: 6,
+                    term_started_at: 5,
                 })],
                 score: Default::default(),
+                claim_operators: vec![],
+                closed_jars: None,
             })
         )
     }
@@ -0,0 +1,162 @@
+use near_sdk::{collections::UnorderedMap, env, near, store::LookupMap, AccountId, PanicOnDefault};
+use sweat_jar_model::{jar::JarId, ProductId, Score};
+
+use super::{account_jars_non_versioned::AccountJarsNonVersioned, legacy_accounts::ContractBeforeLegacyAccounts};
+use crate::{
+    common::Duration,
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::{Apy, Cap, Product, StreakBonus, Terms, WithdrawalFee},
+    MigrationState, StorageKey,
+};
+
+/// Product layout before fee split was introduced.
+#[near]
+#[derive(Clone, Debug)]
+pub struct ProductBeforeFeeSplit {
+    pub id: ProductId,
+    pub apy: Apy,
+    pub cap: Cap,
+    pub terms: Terms,
+    pub withdrawal_fee: Option<WithdrawalFee>,
+    pub public_key: Option<Vec<u8>>,
+    pub is_enabled: bool,
+    pub score_cap: Score,
+    pub streak: Option<StreakBonus>,
+    pub unbonding_period: Option<Duration>,
+}
+
+impl From<ProductBeforeFeeSplit> for Product {
+    fn from(value: ProductBeforeFeeSplit) -> Self {
+        Self {
+            id: value.id,
+            apy: value.apy,
+            cap: value.cap,
+            terms: value.terms,
+            withdrawal_fee: value.withdrawal_fee,
+            public_key: value.public_key,
+            is_enabled: value.is_enabled,
+            score_cap: value.score_cap,
+            streak: value.streak,
+            unbonding_period: value.unbonding_period,
+            fee_split: None,
+        }
+    }
+}
+
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeFeeSplit {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, ProductBeforeFeeSplit>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+}
+
+/// Moves products to the layout with fee split.
+pub(super) fn migrate_state() {
+    let mut old_state: ContractBeforeFeeSplit = env::state_read().expect("Failed to extract old contract state.");
+
+    let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV6);
+
+    for (id, product) in old_state.products.iter() {
+        products.insert(&id, &Product::from(product));
+    }
+
+    old_state.products.clear();
+
+    env::state_write(&ContractBeforeLegacyAccounts {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        collections::UnorderedMap,
+        store::{LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::{api::StateMigration, UDecimal, MS_IN_DAY};
+
+    use crate::{
+        common::tests::Context,
+        migration::{
+            fee_split::{ContractBeforeFeeSplit, ProductBeforeFeeSplit},
+            registry::write_state_fixture,
+        },
+        product::model::{Apy, Cap, StreakBonus, Terms},
+        test_utils::{admin, PRODUCT},
+        Contract, MigrationState, StorageKey,
+    };
+
+    #[test]
+    fn migrate_products_to_fee_split() {
+        let _ctx = Context::new(admin());
+
+        let streak = StreakBonus {
+            threshold: 5_000,
+            step: UDecimal::new(5, 2),
+            max_days: 7,
+        };
+
+        let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV5);
+        products.insert(
+            &PRODUCT.to_string(),
+            &ProductBeforeFeeSplit {
+                id: PRODUCT.to_string(),
+                apy: Apy::Constant(UDecimal::new(12, 2)),
+                cap: Cap { min: 10, max: 100 },
+                terms: Terms::Flexible,
+                withdrawal_fee: None,
+                public_key: None,
+                is_enabled: true,
+                score_cap: 0,
+                streak: Some(streak.clone()),
+                unbonding_period: Some(MS_IN_DAY),
+            },
+        );
+
+        write_state_fixture(
+            &ContractBeforeFeeSplit {
+                token_account_id: "token".parse().unwrap(),
+                fee_account_id: "fee".parse().unwrap(),
+                manager: admin(),
+                products,
+                last_jar_id: 10,
+                accounts: LookupMap::new(StorageKey::Accounts),
+                account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+                account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+                migration: MigrationState {
+                    new_version_account_id: alice(),
+                    migrating_accounts: LookupSet::new(StorageKey::Migration),
+                },
+            },
+            3,
+        );
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(contract.last_jar_id, 10);
+        assert_eq!(contract.products.len(), 1);
+
+        let product = contract.get_product(&PRODUCT.to_string());
+
+        assert_eq!(product.terms, Terms::Flexible);
+        assert_eq!(product.streak, Some(streak));
+        assert_eq!(product.unbonding_period, Some(MS_IN_DAY));
+        assert!(product.fee_split.is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use near_sdk::{
+    collections::UnorderedMap,
+    env, near,
+    store::{IterableMap, LookupMap, LookupSet},
+    AccountId, PanicOnDefault,
+};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned, lock_timestamps::ContractBeforeLockTimestamps,
+    receipts::MigrationReceipt,
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::versioned::ProductVersioned,
+    upgrade::StagedUpgrade,
+    LegacyAccountsMigration, MigrationState, StorageKey,
+};
+
+/// Contract layout before products were moved to `IterableMap`.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeIterableProducts {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, ProductVersioned>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+    pub legacy_accounts: LegacyAccountsMigration,
+    pub migration_receipts: LookupMap<AccountId, MigrationReceipt>,
+    pub staged_upgrade: Option<StagedUpgrade>,
+    pub migrated_products: LookupSet<ProductId>,
+}
+
+/// Moves products from deprecated `UnorderedMap` to `IterableMap`, which caches deserialized values.
+pub(super) fn migrate_state() {
+    let mut old_state: ContractBeforeIterableProducts =
+        env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&ContractBeforeLockTimestamps {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: to_iterable_products(&mut old_state.products),
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: old_state.legacy_accounts,
+        migration_receipts: old_state.migration_receipts,
+        staged_upgrade: old_state.staged_upgrade,
+        migrated_products: old_state.migrated_products,
+    });
+}
+
+fn to_iterable_products(
+    products: &mut UnorderedMap<ProductId, ProductVersioned>,
+) -> IterableMap<ProductId, ProductVersioned> {
+    let mut result = IterableMap::new(StorageKey::Products);
+
+    for (id, product) in products.iter() {
+        result.insert(id, product);
+    }
+
+    products.clear();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        collections::UnorderedMap,
+        store::{LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::api::{ProductApi, StateMigration};
+
+    use crate::{
+        common::tests::Context,
+        migration::{iterable_products::ContractBeforeIterableProducts, registry::write_state_fixture},
+        product::model::Product,
+        test_utils::admin,
+        Contract, LegacyAccountsMigration, MigrationState, StorageKey,
+    };
+
+    #[test]
+    fn migrate_products_to_iterable_map() {
+        let _ctx = Context::new(admin());
+
+        let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV7);
+        products.insert(&"product_1".to_string(), &Product::new().id("product_1").into());
+        products.insert(
+            &"product_2".to_string(),
+            &Product::new().id("product_2").flexible().into(),
+        );
+
+        let state = ContractBeforeIterableProducts {
+            token_account_id: "token".parse().unwrap(),
+            fee_account_id: "fee".parse().unwrap(),
+            manager: admin(),
+            products,
+            last_jar_id: 10,
+            accounts: LookupMap::new(StorageKey::Accounts),
+            account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+            account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+            migration: MigrationState {
+                new_version_account_id: alice(),
+                migrating_accounts: LookupSet::new(StorageKey::Migration),
+            },
+            legacy_accounts: LegacyAccountsMigration::default(),
+            migration_receipts: LookupMap::new(StorageKey::MigrationReceipts),
+            staged_upgrade: None,
+            migrated_products: LookupSet::new(StorageKey::MigratedProducts),
+        };
+
+        write_state_fixture(&state, 9);
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(contract.products.len(), 2);
+        assert_eq!(contract.get_products().len(), 2);
+        assert!(contract.get_product(&"product_2".to_string()).is_flexible());
+    }
+}
@@ -0,0 +1,228 @@
+use near_sdk::{
+    collections::UnorderedMap, env, near, near_bindgen, require, store::LookupMap, AccountId, PanicOnDefault,
+};
+use sweat_jar_model::{api::LegacyAccountsMigrationApi, jar::JarId, migration::LegacyAccountsMigrationView, ProductId};
+
+use super::{account_jars_non_versioned::AccountJarsNonVersioned, receipts::ContractBeforeMigrationReceipts};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::Product,
+    Contract, ContractExt, LegacyAccountsMigration, MigrationState,
+};
+
+/// Contract layout before progress of legacy accounts migration was tracked.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeLegacyAccounts {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, Product>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+}
+
+/// Adds tracking of legacy accounts migration.
+pub(super) fn migrate_state() {
+    let old_state: ContractBeforeLegacyAccounts = env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&ContractBeforeMigrationReceipts {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: LegacyAccountsMigration::default(),
+    });
+}
+
+impl LegacyAccountsMigration {
+    pub(crate) fn on_account_migrated(&mut self) {
+        self.remaining = self.remaining.map(|remaining| remaining.saturating_sub(1));
+        self.migrated += 1;
+    }
+}
+
+impl From<&LegacyAccountsMigration> for LegacyAccountsMigrationView {
+    fn from(value: &LegacyAccountsMigration) -> Self {
+        Self {
+            remaining: value.remaining,
+            migrated: value.migrated,
+            is_complete: value.is_complete,
+        }
+    }
+}
+
+#[near_bindgen]
+impl LegacyAccountsMigrationApi for Contract {
+    fn set_legacy_accounts_count(&mut self, count: u32) {
+        self.assert_manager();
+        require!(
+            !self.legacy_accounts.is_complete,
+            "Legacy accounts migration is complete"
+        );
+
+        self.legacy_accounts.remaining = Some(count);
+    }
+
+    fn migrate_legacy_accounts(&mut self, accounts: Vec<AccountId>) -> LegacyAccountsMigrationView {
+        self.assert_manager();
+        require!(
+            !self.legacy_accounts.is_complete,
+            "Legacy accounts migration is complete"
+        );
+
+        for account_id in accounts {
+            self.assert_account_is_not_migrating(&account_id);
+            self.migrate_account_if_needed(&account_id);
+        }
+
+        (&self.legacy_accounts).into()
+    }
+
+    fn complete_legacy_accounts_migration(&mut self) {
+        self.assert_manager();
+
+        match self.legacy_accounts.remaining {
+            Some(0) => {}
+            Some(remaining) => env::panic_str(&format!("There are {remaining} accounts left in legacy storages")),
+            None => env::panic_str("Number of accounts in legacy storages is not set"),
+        }
+
+        self.legacy_accounts.is_complete = true;
+    }
+
+    fn get_legacy_accounts_migration(&self) -> LegacyAccountsMigrationView {
+        (&self.legacy_accounts).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::test_env::{alice, bob};
+    use sweat_jar_model::{api::LegacyAccountsMigrationApi, migration::LegacyAccountsMigrationView};
+
+    use crate::{
+        common::tests::Context,
+        jar::model::{AccountJarsLegacy, JarLegacy},
+        test_utils::{admin, expect_panic},
+    };
+
+    fn legacy_jars(id: u32) -> AccountJarsLegacy {
+        AccountJarsLegacy {
+            last_id: id,
+            jars: vec![JarLegacy {
+                id,
+                account_id: alice(),
+                product_id: "product".to_string(),
+                created_at: 0,
+                principal: 100,
+                cache: None,
+                claimed_balance: 0,
+                is_pending_withdraw: false,
+                is_penalty_applied: false,
+            }],
+        }
+    }
+
+    fn prepare_context() -> Context {
+        let mut ctx = Context::new(admin());
+
+        {
+            let mut contract = ctx.contract();
+            contract.legacy_accounts.is_complete = false;
+            contract.account_jars_v1.insert(alice(), legacy_jars(1));
+            contract.account_jars_v1.insert(bob(), legacy_jars(2));
+        }
+
+        ctx.switch_account(admin());
+        ctx
+    }
+
+    #[test]
+    fn migrate_legacy_accounts_in_batches() {
+        let ctx = prepare_context();
+
+        ctx.contract().set_legacy_accounts_count(2);
+
+        assert_eq!(
+            ctx.contract().migrate_legacy_accounts(vec![alice()]),
+            LegacyAccountsMigrationView {
+                remaining: Some(1),
+                migrated: 1,
+                is_complete: false,
+            }
+        );
+
+        expect_panic(&ctx, "There are 1 accounts left in legacy storages", || {
+            ctx.contract().complete_legacy_accounts_migration();
+        });
+
+        // Already migrated accounts are skipped
+        let progress = ctx.contract().migrate_legacy_accounts(vec![alice(), bob()]);
+        assert_eq!(progress.remaining, Some(0));
+        assert_eq!(progress.migrated, 2);
+
+        ctx.contract().complete_legacy_accounts_migration();
+
+        let contract = ctx.contract();
+        assert!(contract.get_legacy_accounts_migration().is_complete);
+        assert!(contract.account_jars_v1.get(&alice()).is_none());
+        assert_eq!(contract.accounts.get(&bob()).unwrap().jars.len(), 1);
+    }
+
+    #[test]
+    fn complete_legacy_accounts_migration_with_undercount() {
+        let ctx = prepare_context();
+
+        ctx.contract().set_legacy_accounts_count(1);
+        ctx.contract().migrate_legacy_accounts(vec![alice()]);
+        ctx.contract().complete_legacy_accounts_migration();
+
+        let mut contract = ctx.contract();
+        assert!(contract.get_legacy_accounts_migration().is_complete);
+
+        // Bob wasn't counted, but his jar is still there
+        assert!(contract.has_account(&bob()));
+        assert_eq!(contract.account_jars(&bob()).len(), 1);
+        assert_eq!(contract.get_jar_internal(&bob(), 2).principal, 100);
+
+        contract.migrate_account_if_needed(&bob());
+
+        assert!(contract.account_jars_v1.get(&bob()).is_none());
+        assert_eq!(contract.get_jar_internal(&bob(), 2).principal, 100);
+        assert_eq!(contract.get_legacy_accounts_migration().migrated, 2);
+    }
+
+    #[test]
+    fn migrate_legacy_accounts_not_by_manager() {
+        let mut ctx = prepare_context();
+
+        ctx.switch_account(alice());
+
+        expect_panic(&ctx, "Can be performed only by admin", || {
+            ctx.contract().migrate_legacy_accounts(vec![alice()]);
+        });
+    }
+
+    #[test]
+    fn complete_legacy_accounts_migration_without_count() {
+        let ctx = prepare_context();
+
+        expect_panic(&ctx, "Number of accounts in legacy storages is not set", || {
+            ctx.contract().complete_legacy_accounts_migration();
+        });
+
+        let contract = ctx.contract();
+        assert!(!contract.get_legacy_accounts_migration().is_complete);
+        assert!(contract.has_account(&alice()));
+        assert_eq!(contract.account_jars(&alice()).len(), 1);
+    }
+}
@@ -0,0 +1,108 @@
+use near_sdk::{
+    env, near,
+    store::{IterableMap, LookupMap, LookupSet},
+    AccountId, PanicOnDefault,
+};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned, migrating_products::ContractBeforeMigratingProducts,
+    receipts::MigrationReceipt,
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::versioned::ProductVersioned,
+    upgrade::StagedUpgrade,
+    LegacyAccountsMigration, MigrationState,
+};
+
+/// Contract layout before the time since jar locks are recorded was stored.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeLockTimestamps {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: IterableMap<ProductId, ProductVersioned>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+    pub legacy_accounts: LegacyAccountsMigration,
+    pub migration_receipts: LookupMap<AccountId, MigrationReceipt>,
+    pub staged_upgrade: Option<StagedUpgrade>,
+    pub migrated_products: LookupSet<ProductId>,
+}
+
+/// Jars locked before this upgrade don't have a lock timestamp,
+/// so the stuck lock timeout for them is counted from the upgrade time.
+pub(super) fn migrate_state() {
+    let old_state: ContractBeforeLockTimestamps = env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&ContractBeforeMigratingProducts {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: old_state.legacy_accounts,
+        migration_receipts: old_state.migration_receipts,
+        staged_upgrade: old_state.staged_upgrade,
+        migrated_products: old_state.migrated_products,
+        locks_recorded_since: env::block_timestamp_ms(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        store::{IterableMap, LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::api::StateMigration;
+
+    use crate::{
+        common::tests::Context,
+        migration::{lock_timestamps::ContractBeforeLockTimestamps, registry::write_state_fixture},
+        test_utils::admin,
+        Contract, LegacyAccountsMigration, MigrationState, StorageKey,
+    };
+
+    #[test]
+    fn migrate_state_records_lock_timestamps_start() {
+        let mut ctx = Context::new(admin());
+
+        let state = ContractBeforeLockTimestamps {
+            token_account_id: "token".parse().unwrap(),
+            fee_account_id: "fee".parse().unwrap(),
+            manager: admin(),
+            products: IterableMap::new(StorageKey::Products),
+            last_jar_id: 10,
+            accounts: LookupMap::new(StorageKey::Accounts),
+            account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+            account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+            migration: MigrationState {
+                new_version_account_id: alice(),
+                migrating_accounts: LookupSet::new(StorageKey::Migration),
+            },
+            legacy_accounts: LegacyAccountsMigration::default(),
+            migration_receipts: LookupMap::new(StorageKey::MigrationReceipts),
+            staged_upgrade: None,
+            migrated_products: LookupSet::new(StorageKey::MigratedProducts),
+        };
+
+        write_state_fixture(&state, 10);
+
+        ctx.set_block_timestamp_in_ms(1_000);
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(contract.locks_recorded_since, 1_000);
+        assert_eq!(contract.last_jar_id, 10);
+    }
+}
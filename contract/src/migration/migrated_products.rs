@@ -0,0 +1,57 @@
+use near_sdk::{
+    collections::UnorderedMap,
+    env, near,
+    store::{LookupMap, LookupSet},
+    AccountId, PanicOnDefault,
+};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned, receipts::MigrationReceipt,
+    versioned_products::ContractBeforeVersionedProducts,
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::Product,
+    upgrade::StagedUpgrade,
+    LegacyAccountsMigration, MigrationState, StorageKey,
+};
+
+/// Contract layout before acknowledgments of migrated products were recorded.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeMigratedProducts {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, Product>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+    pub legacy_accounts: LegacyAccountsMigration,
+    pub migration_receipts: LookupMap<AccountId, MigrationReceipt>,
+    pub staged_upgrade: Option<StagedUpgrade>,
+}
+
+/// Adds a set of products acknowledged by v2 contract.
+pub(super) fn migrate_state() {
+    let old_state: ContractBeforeMigratedProducts = env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&ContractBeforeVersionedProducts {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: old_state.legacy_accounts,
+        migration_receipts: old_state.migration_receipts,
+        staged_upgrade: old_state.staged_upgrade,
+        migrated_products: LookupSet::new(StorageKey::MigratedProducts),
+    });
+}
@@ -0,0 +1,106 @@
+use near_sdk::{
+    env, near,
+    store::{IterableMap, LookupMap, LookupSet},
+    AccountId, PanicOnDefault,
+};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{account_jars_non_versioned::AccountJarsNonVersioned, receipts::MigrationReceipt};
+use crate::{
+    common::Timestamp,
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::versioned::ProductVersioned,
+    upgrade::StagedUpgrade,
+    Contract, LegacyAccountsMigration, MigrationState, StorageKey,
+};
+
+/// Contract layout before products sent to v2 contract were marked.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeMigratingProducts {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: IterableMap<ProductId, ProductVersioned>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+    pub legacy_accounts: LegacyAccountsMigration,
+    pub migration_receipts: LookupMap<AccountId, MigrationReceipt>,
+    pub staged_upgrade: Option<StagedUpgrade>,
+    pub migrated_products: LookupSet<ProductId>,
+    pub locks_recorded_since: Timestamp,
+}
+
+/// Adds storage for products waiting for v2 contract response.
+pub(super) fn migrate_state() {
+    let old_state: ContractBeforeMigratingProducts = env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&Contract {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: old_state.legacy_accounts,
+        migration_receipts: old_state.migration_receipts,
+        staged_upgrade: old_state.staged_upgrade,
+        migrated_products: old_state.migrated_products,
+        locks_recorded_since: old_state.locks_recorded_since,
+        migrating_products: LookupSet::new(StorageKey::MigratingProducts),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        store::{IterableMap, LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::api::StateMigration;
+
+    use crate::{
+        common::tests::Context,
+        migration::{migrating_products::ContractBeforeMigratingProducts, registry::write_state_fixture},
+        test_utils::admin,
+        Contract, LegacyAccountsMigration, MigrationState, StorageKey,
+    };
+
+    #[test]
+    fn migrate_state_adds_migrating_products() {
+        let _ctx = Context::new(admin());
+
+        let state = ContractBeforeMigratingProducts {
+            token_account_id: "token".parse().unwrap(),
+            fee_account_id: "fee".parse().unwrap(),
+            manager: admin(),
+            products: IterableMap::new(StorageKey::Products),
+            last_jar_id: 10,
+            accounts: LookupMap::new(StorageKey::Accounts),
+            account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+            account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+            migration: MigrationState {
+                new_version_account_id: alice(),
+                migrating_accounts: LookupSet::new(StorageKey::Migration),
+            },
+            legacy_accounts: LegacyAccountsMigration::default(),
+            migration_receipts: LookupMap::new(StorageKey::MigrationReceipts),
+            staged_upgrade: None,
+            migrated_products: LookupSet::new(StorageKey::MigratedProducts),
+            locks_recorded_since: 1_000,
+        };
+
+        write_state_fixture(&state, 11);
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(contract.locks_recorded_since, 1_000);
+        assert!(!contract.migrating_products.contains(&"product".to_string()));
+    }
+}
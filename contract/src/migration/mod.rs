@@ -1,4 +1,17 @@
 pub mod account_jars_non_versioned;
 pub mod api;
 pub mod claim_rounding_error;
+pub mod fee_split;
+pub mod iterable_products;
+pub mod legacy_accounts;
+pub mod lock_timestamps;
+pub mod migrated_products;
+pub mod migrating_products;
+pub mod receipts;
+pub mod registry;
+pub mod staged_upgrade;
+pub mod streaks;
+pub mod unbonding;
 pub mod v2;
+pub mod v2_ready;
+pub mod versioned_products;
@@ -0,0 +1,75 @@
+use near_sdk::{collections::UnorderedMap, env, near, store::LookupMap, AccountId, PanicOnDefault};
+use sweat_jar_model::{jar::JarId, migration::MigrationReceiptView, ProductId, TokenAmount, U32};
+
+use super::{account_jars_non_versioned::AccountJarsNonVersioned, staged_upgrade::ContractBeforeStagedUpgrade};
+use crate::{
+    common::Timestamp,
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::Product,
+    LegacyAccountsMigration, MigrationState, StorageKey,
+};
+
+/// Evidence of an account migrated to v2 contract. It's kept after the account is cleared.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationReceipt {
+    /// Tokens transferred to v2 contract.
+    pub principal: TokenAmount,
+
+    /// Accrued interest carried to v2 contract in jar caches.
+    pub interest: TokenAmount,
+
+    /// IDs of jars merged into v2 account.
+    pub jar_ids: Vec<JarId>,
+
+    pub target_contract: AccountId,
+
+    pub migrated_at: Timestamp,
+}
+
+impl From<MigrationReceipt> for MigrationReceiptView {
+    fn from(value: MigrationReceipt) -> Self {
+        Self {
+            principal: value.principal.into(),
+            interest: value.interest.into(),
+            jar_ids: value.jar_ids.into_iter().map(U32).collect(),
+            target_contract: value.target_contract,
+            migrated_at: value.migrated_at.into(),
+        }
+    }
+}
+
+/// Contract layout before migration receipts were stored.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeMigrationReceipts {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, Product>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+    pub legacy_accounts: LegacyAccountsMigration,
+}
+
+/// Adds storage for migration receipts.
+pub(super) fn migrate_state() {
+    let old_state: ContractBeforeMigrationReceipts = env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&ContractBeforeStagedUpgrade {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: old_state.legacy_accounts,
+        migration_receipts: LookupMap::new(StorageKey::MigrationReceipts),
+    });
+}
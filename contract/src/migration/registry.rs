@@ -0,0 +1,273 @@
+use near_sdk::{
+    borsh::{to_vec, BorshDeserialize, BorshSerialize},
+    env::{self, log_str, panic_str},
+    near, require, AccountId,
+};
+use sweat_jar_model::api::StateMigration;
+
+use super::{
+    fee_split, iterable_products, legacy_accounts, lock_timestamps, migrated_products, migrating_products, receipts,
+    staged_upgrade, streaks, unbonding, v2_ready, versioned_products,
+};
+use crate::{Contract, ContractExt};
+
+/// Version of the contract state layout.
+pub(crate) type StateVersion = u32;
+
+/// Every change of the state layout adds a step to `MIGRATIONS` and increments this version.
+///
+/// 0. Accounts migration to v2 contract isn't configured
+/// 1. Products without streak bonuses
+/// 2. Products without unbonding period
+/// 3. Products without fee split
+/// 4. Products with fee split
+/// 5. Progress of legacy accounts migration is tracked
+/// 6. Receipts of accounts migrated to v2 contract are stored
+/// 7. Upgrades of the contract code are staged
+/// 8. Products acknowledged by v2 contract are recorded
+/// 9. Products are stored with version tags
+/// 10. Products are stored in `IterableMap`
+/// 11. Time since jar locks are recorded is stored
+/// 12. Products sent to v2 contract are marked until it responds
+pub(crate) const CURRENT_STATE_VERSION: StateVersion = 12;
+
+/// The version is stored under a raw key outside of the `Contract` struct,
+/// so it can be read before the state is deserialized and doesn't change the state layout.
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+
+/// Arguments of `migrate_state` required by some of the steps.
+pub(super) struct MigrationArgs {
+    pub new_version_account_id: Option<AccountId>,
+}
+
+/// A step converting the state of `from` version to the state of the next version.
+struct MigrationStep {
+    from: StateVersion,
+    name: &'static str,
+    migrate: fn(&MigrationArgs),
+}
+
+const MIGRATIONS: &[MigrationStep] = &[
+    MigrationStep {
+        from: 0,
+        name: "v2_ready",
+        migrate: v2_ready::migrate_state,
+    },
+    MigrationStep {
+        from: 1,
+        name: "streaks",
+        migrate: |_| streaks::migrate_state(),
+    },
+    MigrationStep {
+        from: 2,
+        name: "unbonding",
+        migrate: |_| unbonding::migrate_state(),
+    },
+    MigrationStep {
+        from: 3,
+        name: "fee_split",
+        migrate: |_| fee_split::migrate_state(),
+    },
+    MigrationStep {
+        from: 4,
+        name: "legacy_accounts",
+        migrate: |_| legacy_accounts::migrate_state(),
+    },
+    MigrationStep {
+        from: 5,
+        name: "migration_receipts",
+        migrate: |_| receipts::migrate_state(),
+    },
+    MigrationStep {
+        from: 6,
+        name: "staged_upgrade",
+        migrate: |_| staged_upgrade::migrate_state(),
+    },
+    MigrationStep {
+        from: 7,
+        name: "migrated_products",
+        migrate: |_| migrated_products::migrate_state(),
+    },
+    MigrationStep {
+        from: 8,
+        name: "versioned_products",
+        migrate: |_| versioned_products::migrate_state(),
+    },
+    MigrationStep {
+        from: 9,
+        name: "iterable_products",
+        migrate: |_| iterable_products::migrate_state(),
+    },
+    MigrationStep {
+        from: 10,
+        name: "lock_timestamps",
+        migrate: |_| lock_timestamps::migrate_state(),
+    },
+    MigrationStep {
+        from: 11,
+        name: "migrating_products",
+        migrate: |_| migrating_products::migrate_state(),
+    },
+];
+
+pub(crate) fn read_state_version() -> Option<StateVersion> {
+    env::storage_read(STATE_VERSION_KEY)
+        .map(|bytes| StateVersion::try_from_slice(&bytes).expect("Failed to read state version"))
+}
+
+pub(crate) fn write_state_version(version: StateVersion) {
+    env::storage_write(
+        STATE_VERSION_KEY,
+        &to_vec(&version).expect("Failed to serialize state version"),
+    );
+}
+
+#[near]
+impl StateMigration for Contract {
+    #[private]
+    #[init(ignore_state)]
+    fn migrate_state(legacy_version: Option<StateVersion>, new_version_account_id: Option<AccountId>) -> Self {
+        let version = read_state_version()
+            .or(legacy_version)
+            .unwrap_or_else(|| panic_str("State version is not recorded, legacy version is required"));
+
+        require!(version != CURRENT_STATE_VERSION, "State is already migrated");
+        require!(
+            version < CURRENT_STATE_VERSION,
+            format!("Unknown state version: {version}")
+        );
+
+        let args = MigrationArgs { new_version_account_id };
+
+        for step in MIGRATIONS.iter().skip_while(|step| step.from < version) {
+            log_str(&format!("Migrating state from version {}: {}", step.from, step.name));
+            (step.migrate)(&args);
+        }
+
+        write_state_version(CURRENT_STATE_VERSION);
+
+        env::state_read().expect("Failed to extract migrated contract state.")
+    }
+}
+
+/// Writes the state of an old layout, so migrations can be run against it in unit tests.
+#[cfg(test)]
+pub(crate) fn write_state_fixture<T: BorshSerialize>(state: &T, version: StateVersion) {
+    env::state_write(state);
+    write_state_version(version);
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        collections::UnorderedMap,
+        env,
+        store::{LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::{api::StateMigration, UDecimal};
+
+    use super::{read_state_version, write_state_fixture, CURRENT_STATE_VERSION, MIGRATIONS, STATE_VERSION_KEY};
+    use crate::{
+        common::tests::Context,
+        migration::streaks::{ContractBeforeStreaks, ProductBeforeStreaks},
+        product::model::{Apy, Cap, Terms},
+        test_utils::{admin, expect_panic, PRODUCT},
+        Contract, MigrationState, StorageKey,
+    };
+
+    fn state_before_streaks() -> ContractBeforeStreaks {
+        let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV3);
+        products.insert(
+            &PRODUCT.to_string(),
+            &ProductBeforeStreaks {
+                id: PRODUCT.to_string(),
+                apy: Apy::Constant(UDecimal::new(12, 2)),
+                cap: Cap { min: 10, max: 100 },
+                terms: Terms::Flexible,
+                withdrawal_fee: None,
+                public_key: None,
+                is_enabled: true,
+                score_cap: 0,
+            },
+        );
+
+        ContractBeforeStreaks {
+            token_account_id: "token".parse().unwrap(),
+            fee_account_id: "fee".parse().unwrap(),
+            manager: admin(),
+            products,
+            last_jar_id: 10,
+            accounts: LookupMap::new(StorageKey::Accounts),
+            account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+            account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+            migration: MigrationState {
+                new_version_account_id: alice(),
+                migrating_accounts: LookupSet::new(StorageKey::Migration),
+            },
+        }
+    }
+
+    #[test]
+    fn migration_steps_cover_all_versions() {
+        let versions: Vec<_> = MIGRATIONS.iter().map(|step| step.from).collect();
+        let expected: Vec<_> = (0..CURRENT_STATE_VERSION).collect();
+
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn init_records_current_state_version() {
+        let _ctx = Context::new(admin());
+
+        assert_eq!(read_state_version(), Some(CURRENT_STATE_VERSION));
+    }
+
+    #[test]
+    fn migrate_state_through_all_steps() {
+        let _ctx = Context::new(admin());
+
+        write_state_fixture(&state_before_streaks(), 1);
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(read_state_version(), Some(CURRENT_STATE_VERSION));
+        assert_eq!(contract.last_jar_id, 10);
+        assert_eq!(contract.products.len(), 1);
+
+        let product = contract.get_product(&PRODUCT.to_string());
+
+        assert_eq!(product.cap.max, 100);
+        assert!(product.streak.is_none());
+        assert!(product.unbonding_period.is_none());
+        assert!(product.fee_split.is_none());
+        assert!(!contract.legacy_accounts.is_complete);
+        assert!(contract.staged_upgrade.is_none());
+    }
+
+    #[test]
+    fn migrate_legacy_state_without_recorded_version() {
+        let _ctx = Context::new(admin());
+
+        env::storage_remove(STATE_VERSION_KEY);
+        env::state_write(&state_before_streaks());
+
+        let contract = Contract::migrate_state(Some(1), None);
+
+        assert_eq!(read_state_version(), Some(CURRENT_STATE_VERSION));
+        assert_eq!(contract.products.len(), 1);
+    }
+
+    #[test]
+    fn migrate_state_twice() {
+        let ctx = Context::new(admin());
+
+        expect_panic(&ctx, "State is already migrated", || {
+            Contract::migrate_state(None, None);
+        });
+
+        expect_panic(&ctx, "State is already migrated", || {
+            Contract::migrate_state(Some(1), None);
+        });
+    }
+}
@@ -0,0 +1,49 @@
+use near_sdk::{collections::UnorderedMap, env, near, store::LookupMap, AccountId, PanicOnDefault};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned, migrated_products::ContractBeforeMigratedProducts,
+    receipts::MigrationReceipt,
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::Product,
+    LegacyAccountsMigration, MigrationState,
+};
+
+/// Contract layout before upgrades were staged.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeStagedUpgrade {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, Product>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+    pub legacy_accounts: LegacyAccountsMigration,
+    pub migration_receipts: LookupMap<AccountId, MigrationReceipt>,
+}
+
+/// Adds a slot for a staged upgrade.
+pub(super) fn migrate_state() {
+    let old_state: ContractBeforeStagedUpgrade = env::state_read().expect("Failed to extract old contract state.");
+
+    env::state_write(&ContractBeforeMigratedProducts {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+        legacy_accounts: old_state.legacy_accounts,
+        migration_receipts: old_state.migration_receipts,
+        staged_upgrade: None,
+    });
+}
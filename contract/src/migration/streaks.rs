@@ -0,0 +1,155 @@
+use near_sdk::{collections::UnorderedMap, env, near, store::LookupMap, AccountId, PanicOnDefault};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned,
+    unbonding::{ContractBeforeUnbonding, ProductBeforeUnbonding},
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::{Apy, Cap, Terms, WithdrawalFee},
+    MigrationState, StorageKey,
+};
+
+/// Product layout before streak bonuses were introduced.
+#[near]
+#[derive(Clone, Debug)]
+pub struct ProductBeforeStreaks {
+    pub id: ProductId,
+    pub apy: Apy,
+    pub cap: Cap,
+    pub terms: Terms,
+    pub withdrawal_fee: Option<WithdrawalFee>,
+    pub public_key: Option<Vec<u8>>,
+    pub is_enabled: bool,
+    pub score_cap: u16,
+}
+
+impl From<ProductBeforeStreaks> for ProductBeforeUnbonding {
+    fn from(value: ProductBeforeStreaks) -> Self {
+        Self {
+            id: value.id,
+            apy: value.apy,
+            cap: value.cap,
+            terms: value.terms,
+            withdrawal_fee: value.withdrawal_fee,
+            public_key: value.public_key,
+            is_enabled: value.is_enabled,
+            score_cap: value.score_cap.into(),
+            streak: None,
+        }
+    }
+}
+
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeStreaks {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, ProductBeforeStreaks>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+}
+
+/// Moves products to the layout with streak bonuses and widens their score cap to `u32`.
+pub(super) fn migrate_state() {
+    let mut old_state: ContractBeforeStreaks = env::state_read().expect("Failed to extract old contract state.");
+
+    let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV4);
+
+    for (id, product) in old_state.products.iter() {
+        products.insert(&id, &ProductBeforeUnbonding::from(product));
+    }
+
+    old_state.products.clear();
+
+    env::state_write(&ContractBeforeUnbonding {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        collections::UnorderedMap,
+        store::{LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::{api::StateMigration, UDecimal, MS_IN_YEAR};
+
+    use crate::{
+        common::tests::Context,
+        migration::{
+            registry::write_state_fixture,
+            streaks::{ContractBeforeStreaks, ProductBeforeStreaks},
+        },
+        product::model::{Apy, Cap, FixedProductTerms, Terms},
+        test_utils::{admin, SCORE_PRODUCT},
+        Contract, MigrationState, StorageKey,
+    };
+
+    #[test]
+    fn migrate_products_to_streaks() {
+        let _ctx = Context::new(admin());
+
+        let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV3);
+        products.insert(
+            &SCORE_PRODUCT.to_string(),
+            &ProductBeforeStreaks {
+                id: SCORE_PRODUCT.to_string(),
+                apy: Apy::Constant(UDecimal::new(0, 0)),
+                cap: Cap { min: 10, max: 100 },
+                terms: Terms::Fixed(FixedProductTerms {
+                    lockup_term: MS_IN_YEAR,
+                    allows_top_up: false,
+                    allows_restaking: false,
+                }),
+                withdrawal_fee: None,
+                public_key: None,
+                is_enabled: true,
+                score_cap: 20_000,
+            },
+        );
+
+        write_state_fixture(
+            &ContractBeforeStreaks {
+                token_account_id: "token".parse().unwrap(),
+                fee_account_id: "fee".parse().unwrap(),
+                manager: admin(),
+                products,
+                last_jar_id: 10,
+                accounts: LookupMap::new(StorageKey::Accounts),
+                account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+                account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+                migration: MigrationState {
+                    new_version_account_id: alice(),
+                    migrating_accounts: LookupSet::new(StorageKey::Migration),
+                },
+            },
+            1,
+        );
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(contract.last_jar_id, 10);
+        assert_eq!(contract.products.len(), 1);
+
+        let product = contract.get_product(&SCORE_PRODUCT.to_string());
+
+        assert_eq!(product.score_cap, 20_000);
+        assert_eq!(product.cap.max, 100);
+        assert!(product.streak.is_none());
+    }
+}
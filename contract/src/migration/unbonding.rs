@@ -0,0 +1,160 @@
+use near_sdk::{collections::UnorderedMap, env, near, store::LookupMap, AccountId, PanicOnDefault};
+use sweat_jar_model::{jar::JarId, ProductId, Score};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned,
+    fee_split::{ContractBeforeFeeSplit, ProductBeforeFeeSplit},
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    product::model::{Apy, Cap, StreakBonus, Terms, WithdrawalFee},
+    MigrationState, StorageKey,
+};
+
+/// Product layout before unbonding period was introduced.
+#[near]
+#[derive(Clone, Debug)]
+pub struct ProductBeforeUnbonding {
+    pub id: ProductId,
+    pub apy: Apy,
+    pub cap: Cap,
+    pub terms: Terms,
+    pub withdrawal_fee: Option<WithdrawalFee>,
+    pub public_key: Option<Vec<u8>>,
+    pub is_enabled: bool,
+    pub score_cap: Score,
+    pub streak: Option<StreakBonus>,
+}
+
+impl From<ProductBeforeUnbonding> for ProductBeforeFeeSplit {
+    fn from(value: ProductBeforeUnbonding) -> Self {
+        Self {
+            id: value.id,
+            apy: value.apy,
+            cap: value.cap,
+            terms: value.terms,
+            withdrawal_fee: value.withdrawal_fee,
+            public_key: value.public_key,
+            is_enabled: value.is_enabled,
+            score_cap: value.score_cap,
+            streak: value.streak,
+            unbonding_period: None,
+        }
+    }
+}
+
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeUnbonding {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, ProductBeforeUnbonding>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+    pub migration: MigrationState,
+}
+
+/// Moves products to the layout with unbonding period.
+pub(super) fn migrate_state() {
+    let mut old_state: ContractBeforeUnbonding = env::state_read().expect("Failed to extract old contract state.");
+
+    let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV5);
+
+    for (id, product) in old_state.products.iter() {
+        products.insert(&id, &ProductBeforeFeeSplit::from(product));
+    }
+
+    old_state.products.clear();
+
+    env::state_write(&ContractBeforeFeeSplit {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: old_state.migration,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        collections::UnorderedMap,
+        store::{LookupMap, LookupSet},
+        test_utils::test_env::alice,
+    };
+    use sweat_jar_model::{api::StateMigration, UDecimal};
+
+    use crate::{
+        common::tests::Context,
+        migration::{
+            registry::write_state_fixture,
+            unbonding::{ContractBeforeUnbonding, ProductBeforeUnbonding},
+        },
+        product::model::{Apy, Cap, StreakBonus, Terms},
+        test_utils::{admin, PRODUCT},
+        Contract, MigrationState, StorageKey,
+    };
+
+    #[test]
+    fn migrate_products_to_unbonding() {
+        let _ctx = Context::new(admin());
+
+        let streak = StreakBonus {
+            threshold: 5_000,
+            step: UDecimal::new(5, 2),
+            max_days: 7,
+        };
+
+        let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV4);
+        products.insert(
+            &PRODUCT.to_string(),
+            &ProductBeforeUnbonding {
+                id: PRODUCT.to_string(),
+                apy: Apy::Constant(UDecimal::new(12, 2)),
+                cap: Cap { min: 10, max: 100 },
+                terms: Terms::Flexible,
+                withdrawal_fee: None,
+                public_key: None,
+                is_enabled: true,
+                score_cap: 0,
+                streak: Some(streak.clone()),
+            },
+        );
+
+        write_state_fixture(
+            &ContractBeforeUnbonding {
+                token_account_id: "token".parse().unwrap(),
+                fee_account_id: "fee".parse().unwrap(),
+                manager: admin(),
+                products,
+                last_jar_id: 10,
+                accounts: LookupMap::new(StorageKey::Accounts),
+                account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+                account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+                migration: MigrationState {
+                    new_version_account_id: alice(),
+                    migrating_accounts: LookupSet::new(StorageKey::Migration),
+                },
+            },
+            2,
+        );
+
+        let contract = Contract::migrate_state(None, None);
+
+        assert_eq!(contract.last_jar_id, 10);
+        assert_eq!(contract.products.len(), 1);
+
+        let product = contract.get_product(&PRODUCT.to_string());
+
+        assert_eq!(product.terms, Terms::Flexible);
+        assert_eq!(product.streak, Some(streak));
+        assert!(product.unbonding_period.is_none());
+    }
+}
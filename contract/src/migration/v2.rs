@@ -1,85 +1,42 @@
-use std::{cell::RefCell, collections::HashMap};
-
 use near_sdk::{
     borsh::to_vec,
-    collections::UnorderedMap,
     env::{self, log_str, panic_str},
     json_types::Base64VecU8,
     near, require,
     serde_json::{self, json},
-    store::{LookupMap, LookupSet},
-    AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseOrValue,
+    AccountId, Gas, NearToken, Promise, PromiseOrValue,
 };
 use sweat_jar_model::{
     account::{v1::AccountScore, versioned::AccountVersioned, Account},
     api::MigrationToV2,
-    jar::JarId,
-    ProductId, ScoreRecord, TokenAmount,
+    migration::{AccountMigrationPreview, MigrationBlocker, MigrationReceiptView, ProductsMigrationView},
+    ProductId, Score, ScoreRecord, TokenAmount, U32,
 };
 
-use super::account_jars_non_versioned::AccountJarsNonVersioned;
+use super::receipts::MigrationReceipt;
 #[cfg(not(test))]
 use crate::ft_interface::FungibleTokenInterface;
 use crate::{
     assert::assert_not_locked,
     event::{emit, EventKind},
     internal::{assert_gas, is_promise_success},
-    jar::{account::versioned::Account as LegacyAccount, model::AccountJarsLegacy},
-    product::model::Product,
-    Contract, ContractExt, MigrationState, StorageKey,
+    jar::model::Jar,
+    Contract, ContractExt,
 };
 
-#[near]
-#[derive(PanicOnDefault)]
-pub struct ContractBeforeMigration {
-    pub token_account_id: AccountId,
-    pub fee_account_id: AccountId,
-    pub manager: AccountId,
-    pub products: UnorderedMap<ProductId, Product>,
-    pub last_jar_id: JarId,
-    pub accounts: LookupMap<AccountId, LegacyAccount>,
-    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
-    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
-    #[borsh(skip)]
-    pub products_cache: RefCell<HashMap<ProductId, Product>>,
-    pub migration: MigrationState,
-}
-
 const TGAS_FOR_MIGRATION_TRANSFER: u64 = 100;
 const TGAS_FOR_MIGRATION_CALLBACK: u64 = 10;
 
 #[near]
 #[mutants::skip]
 impl MigrationToV2 for Contract {
-    #[private]
-    #[init(ignore_state)]
-    fn migrate_state_to_v2_ready(new_version_account_id: AccountId) -> Self {
-        let old_state: ContractBeforeMigration = env::state_read().expect("Failed to extract old contract state.");
-
-        Contract {
-            token_account_id: old_state.token_account_id,
-            fee_account_id: old_state.fee_account_id,
-            manager: old_state.manager,
-            products: old_state.products,
-            last_jar_id: old_state.last_jar_id,
-            accounts: old_state.accounts,
-            account_jars_non_versioned: old_state.account_jars_non_versioned,
-            account_jars_v1: old_state.account_jars_v1,
-            products_cache: old_state.products_cache,
-            migration: MigrationState {
-                new_version_account_id,
-                migrating_accounts: LookupSet::new(StorageKey::Migration),
-            },
-        }
-    }
-
     fn migrate_account(&mut self) -> PromiseOrValue<(AccountId, bool)> {
         let account_id = env::predecessor_account_id();
         self.assert_account_exists(&account_id);
         self.assert_account_is_not_migrating(&account_id);
 
-        let Some((principal, memo, msg)) = self.prepare_migration_params(account_id.clone()) else {
-            return self.finalize_migration(account_id, true);
+        let Some((receipt, memo, msg)) = self.prepare_migration_params(account_id.clone()) else {
+            return self.finalize_migration(account_id, None, true);
         };
 
         assert_gas(
@@ -87,7 +44,11 @@ impl MigrationToV2 for Contract {
             || format!("Out of gas in migrate_account({account_id})"),
         );
 
-        self.transfer_account(&account_id, principal, memo, msg)
+        self.transfer_account(&account_id, receipt, memo, msg)
+    }
+
+    fn get_migration_receipt(&self, account_id: AccountId) -> Option<MigrationReceiptView> {
+        self.migration_receipts.get(&account_id).cloned().map(Into::into)
     }
 
     fn is_account_locked(&self, account_id: AccountId) -> bool {
@@ -99,17 +60,110 @@ impl MigrationToV2 for Contract {
         self.migration.migrating_accounts.remove(&account_id);
     }
 
-    fn migrate_products(&mut self) -> PromiseOrValue<()> {
+    fn migrate_products(&mut self, product_ids: Option<Vec<ProductId>>) -> PromiseOrValue<Vec<ProductId>> {
         self.assert_manager();
 
-        let products: Vec<product_v2::Product> = self.products.values().map(Into::into).collect();
+        let product_ids = match product_ids {
+            Some(product_ids) => {
+                for product_id in &product_ids {
+                    require!(
+                        !self.migrated_products.contains(product_id),
+                        format!("Product '{product_id}' is already migrated")
+                    );
+                    require!(
+                        !self.migrating_products.contains(product_id),
+                        format!("Product '{product_id}' is already being migrated")
+                    );
+                }
+                product_ids
+            }
+            None => self.pending_products(),
+        };
+
+        require!(!product_ids.is_empty(), "There are no products to migrate");
+
+        let products: Vec<product_v2::Product> = product_ids
+            .iter()
+            .map(|product_id| self.get_product(product_id).clone().into())
+            .collect();
         let args = json!({
             "products": products
         });
         log_str(&format!("args: {args}"));
         let args_json = serde_json::to_vec(&args).unwrap_or_else(|_| panic_str("Failed to serialize args"));
 
-        self.transfer_products(args_json)
+        assert_gas(
+            Gas::from_tgas(TGAS_FOR_MIGRATION_TRANSFER + TGAS_FOR_MIGRATION_CALLBACK).as_gas(),
+            || format!("Out of gas in migrate_products({} products)", product_ids.len()),
+        );
+
+        for product_id in &product_ids {
+            self.migrating_products.insert(product_id.clone());
+        }
+
+        self.transfer_products(product_ids, args_json)
+    }
+
+    fn get_products_migration(&self) -> ProductsMigrationView {
+        let (mut migrated, mut pending): (Vec<_>, Vec<_>) = self
+            .products
+            .keys()
+            .cloned()
+            .partition(|product_id| self.migrated_products.contains(product_id));
+
+        migrated.sort();
+        pending.sort();
+
+        ProductsMigrationView { migrated, pending }
+    }
+
+    fn preview_account_migration(&self, account_id: AccountId) -> AccountMigrationPreview {
+        let jars = self.account_jars(&account_id);
+        let mut blockers = vec![];
+
+        if jars.is_empty() {
+            blockers.push(MigrationBlocker::NoJars);
+        }
+
+        if self.migration.migrating_accounts.contains(&account_id) {
+            blockers.push(MigrationBlocker::AccountIsMigrating);
+        }
+
+        for jar in &jars {
+            if jar.is_pending_withdraw {
+                blockers.push(MigrationBlocker::JarIsLocked(U32(jar.id)));
+            }
+        }
+
+        let mut missing_products: Vec<ProductId> = vec![];
+
+        for jar in &jars {
+            if !missing_products.contains(&jar.product_id) && self.products.get(&jar.product_id).is_none() {
+                missing_products.push(jar.product_id.clone());
+            }
+        }
+
+        let unsupported_score = self.get_score(&account_id).and_then(unsupported_legacy_score);
+
+        let account = (missing_products.is_empty() && unsupported_score.is_none())
+            .then(|| self.map_legacy_jars(&account_id, &jars).0);
+
+        blockers.extend(missing_products.into_iter().map(MigrationBlocker::MissingProduct));
+        blockers.extend(unsupported_score.map(MigrationBlocker::UnsupportedScore));
+
+        let principal = jars
+            .iter()
+            .map(|jar| jar.principal + jar.unbonding.map_or(0, |unbonding| unbonding.amount))
+            .sum::<TokenAmount>();
+
+        let interest = account.as_ref().map_or(0, carried_interest);
+
+        AccountMigrationPreview {
+            account,
+            principal: principal.into(),
+            interest: interest.into(),
+            blockers,
+        }
     }
 }
 
@@ -119,26 +173,23 @@ impl Contract {
     fn transfer_account(
         &mut self,
         account_id: &AccountId,
-        principal: TokenAmount,
+        receipt: MigrationReceipt,
         memo: String,
         msg: String,
     ) -> PromiseOrValue<(AccountId, bool)> {
         self.ft_contract()
             .ft_transfer_call(
                 &self.migration.new_version_account_id,
-                principal,
+                receipt.principal,
                 memo.as_str(),
                 msg.as_str(),
                 TGAS_FOR_MIGRATION_TRANSFER,
             )
-            .then(
-                Self::ext(env::current_account_id())
-                    .after_account_transferred(account_id.clone()),
-            )
+            .then(Self::ext(env::current_account_id()).after_account_transferred(account_id.clone(), receipt))
             .into()
     }
 
-    fn transfer_products(&mut self, args: Vec<u8>) -> PromiseOrValue<()> {
+    fn transfer_products(&mut self, product_ids: Vec<ProductId>, args: Vec<u8>) -> PromiseOrValue<Vec<ProductId>> {
         Promise::new(self.migration.new_version_account_id.clone())
             .function_call(
                 "migrate_products".to_string(),
@@ -146,7 +197,11 @@ impl Contract {
                 NearToken::from_yoctonear(0),
                 Gas::from_tgas(TGAS_FOR_MIGRATION_TRANSFER),
             )
-            .then(Self::ext(env::current_account_id()).after_products_migrated())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas::from_tgas(TGAS_FOR_MIGRATION_CALLBACK))
+                    .after_products_migrated(product_ids),
+            )
             .into()
     }
 }
@@ -157,41 +212,74 @@ impl Contract {
     fn transfer_account(
         &mut self,
         account_id: &AccountId,
-        _principal: TokenAmount,
+        receipt: MigrationReceipt,
         _memo: String,
         _msg: String,
     ) -> PromiseOrValue<(AccountId, bool)> {
-        self.after_account_transferred(account_id.clone())
+        self.after_account_transferred(account_id.clone(), receipt)
     }
 
-    fn transfer_products(&mut self, _args: Vec<u8>) -> PromiseOrValue<()> {
-        PromiseOrValue::Value(())
+    fn transfer_products(&mut self, product_ids: Vec<ProductId>, _args: Vec<u8>) -> PromiseOrValue<Vec<ProductId>> {
+        self.after_products_migrated(product_ids)
     }
 }
 
 #[near]
 impl Contract {
     #[private]
-    pub fn after_account_transferred(&mut self, account_id: AccountId) -> PromiseOrValue<(AccountId, bool)> {
-        self.finalize_migration(account_id, is_promise_success())
+    pub fn after_account_transferred(
+        &mut self,
+        account_id: AccountId,
+        receipt: MigrationReceipt,
+    ) -> PromiseOrValue<(AccountId, bool)> {
+        self.finalize_migration(account_id, Some(receipt), is_promise_success())
     }
 
+    /// Products are released on failure so they can be sent again,
+    /// that's why this callback doesn't panic and returns an empty list instead.
     #[private]
-    pub fn after_products_migrated(&mut self) -> PromiseOrValue<()> {
-        require!(is_promise_success(), "Products migration failed");
+    pub fn after_products_migrated(&mut self, product_ids: Vec<ProductId>) -> PromiseOrValue<Vec<ProductId>> {
+        let is_success = is_promise_success();
+
+        for product_id in &product_ids {
+            self.migrating_products.remove(product_id);
+
+            if is_success {
+                self.migrated_products.insert(product_id.clone());
+            }
+        }
 
-        PromiseOrValue::Value(())
+        if !is_success {
+            log_str("Products migration failed");
+            return PromiseOrValue::Value(vec![]);
+        }
+
+        PromiseOrValue::Value(product_ids)
     }
 }
 #[mutants::skip]
 impl Contract {
-    fn prepare_migration_params(&mut self, account_id: AccountId) -> Option<(TokenAmount, String, String)> {
-        let (account, principal) = self.map_legacy_account(account_id.clone());
+    fn prepare_migration_params(&mut self, account_id: AccountId) -> Option<(MigrationReceipt, String, String)> {
+        let jars = self.account_jars(&account_id);
+
+        for jar in &jars {
+            assert_not_locked(jar);
+        }
+
+        let (account, principal) = self.map_legacy_jars(&account_id, &jars);
         if account.jars.is_empty() {
             return None;
         }
         self.lock_account(&account_id);
 
+        let receipt = MigrationReceipt {
+            principal,
+            interest: carried_interest(&account),
+            jar_ids: jars.iter().map(|jar| jar.id).collect(),
+            target_contract: self.migration.new_version_account_id.clone(),
+            migrated_at: env::block_timestamp_ms(),
+        };
+
         let account = AccountVersioned::V1(account);
         let account_vec: Base64VecU8 = to_vec(&account)
             .unwrap_or_else(|_| panic_str("Failed to serialize account"))
@@ -206,12 +294,22 @@ impl Contract {
         })
         .to_string();
 
-        Some((principal, memo, msg))
+        Some((receipt, memo, msg))
     }
 
-    fn finalize_migration(&mut self, account_id: AccountId, is_success: bool) -> PromiseOrValue<(AccountId, bool)> {
+    fn finalize_migration(
+        &mut self,
+        account_id: AccountId,
+        receipt: Option<MigrationReceipt>,
+        is_success: bool,
+    ) -> PromiseOrValue<(AccountId, bool)> {
         if is_success {
             self.clear_account(&account_id);
+
+            if let Some(receipt) = receipt {
+                self.migration_receipts.insert(account_id.clone(), receipt);
+            }
+
             emit(EventKind::JarsMerge(account_id.clone()));
         }
 
@@ -220,45 +318,55 @@ impl Contract {
         PromiseOrValue::Value((self.migration.new_version_account_id.clone(), is_success))
     }
 
-    fn map_legacy_account(&self, account_id: AccountId) -> (Account, TokenAmount) {
+    fn map_legacy_jars(&self, account_id: &AccountId, jars: &[Jar]) -> (Account, TokenAmount) {
         let now = env::block_timestamp_ms();
 
         let score = self
-            .get_score(&account_id)
+            .get_score(account_id)
             .map_or_else(ScoreRecord::default, crate::score::AccountScore::claimable_score);
 
         let mut account = Account {
             nonce: 0,
             score: self
-                .get_score(&account_id)
+                .get_score(account_id)
                 .map_or_else(AccountScore::default, |value| AccountScore {
                     updated: value.updated,
                     timezone: value.timezone,
-                    scores: value.scores,
-                    scores_history: value.scores_history,
+                    scores: value.scores.map(to_legacy_score),
+                    scores_history: value.scores_history.map(to_legacy_score),
                 }),
             ..Account::default()
         };
         let mut total_principal = 0;
 
-        let jars = self.account_jars(&account_id);
-        for jar in &jars {
-            assert_not_locked(jar);
+        for jar in jars {
+            // The new contract doesn't support unbonding, so unbonding tokens are migrated as principal
+            let principal = jar.principal + jar.unbonding.map_or(0, |unbonding| unbonding.amount);
 
-            let updated_jar = account.deposit(&jar.product_id, jar.principal, jar.created_at.into());
-            let (interest, remainder) = jar.get_interest(&score, &self.get_product(&jar.product_id), now);
+            let updated_jar = account.deposit(&jar.product_id, principal, jar.term_started_at.into());
+            let (interest, remainder) = jar.get_interest(&score, self.get_product(&jar.product_id), now);
             updated_jar.add_to_cache(now, interest, remainder);
 
             if !account.is_penalty_applied {
                 account.is_penalty_applied = jar.is_penalty_applied;
             }
 
-            total_principal += jar.principal;
+            total_principal += principal;
         }
 
         (account, total_principal)
     }
 
+    fn pending_products(&self) -> Vec<ProductId> {
+        self.products
+            .keys()
+            .filter(|product_id| {
+                !self.migrated_products.contains(*product_id) && !self.migrating_products.contains(*product_id)
+            })
+            .cloned()
+            .collect()
+    }
+
     fn lock_account(&mut self, account_id: &AccountId) {
         self.migration.migrating_accounts.insert(account_id.clone());
     }
@@ -269,18 +377,239 @@ impl Contract {
 
     fn clear_account(&mut self, account_id: &AccountId) {
         self.accounts.remove(account_id);
-        self.account_jars_v1.remove(account_id);
-        self.account_jars_non_versioned.remove(account_id);
+
+        let is_legacy_v1 = self.account_jars_v1.remove(account_id).is_some();
+        let is_legacy_non_versioned = self.account_jars_non_versioned.remove(account_id).is_some();
+
+        if is_legacy_v1 || is_legacy_non_versioned {
+            self.legacy_accounts.on_account_migrated();
+        }
     }
 }
 
+/// Interest accrued by legacy jars is moved to interest caches of v2 account jars.
+fn carried_interest(account: &Account) -> TokenAmount {
+    account
+        .jars
+        .values()
+        .map(|jar| jar.cache.map_or(0, |cache| cache.interest))
+        .sum()
+}
+
+/// v2 contract stores scores as `u16`. Bigger scores can't be migrated without losing them.
+fn to_legacy_score(score: Score) -> u16 {
+    u16::try_from(score).unwrap_or_else(|_| panic_str(&format!("Score {score} doesn't fit into v2 account")))
+}
+
+/// Returns a score which doesn't fit into v2 account, if there is any.
+fn unsupported_legacy_score(score: &crate::score::AccountScore) -> Option<Score> {
+    score
+        .scores
+        .into_iter()
+        .chain(score.scores_history)
+        .find(|score| u16::try_from(*score).is_err())
+}
+
 #[cfg(test)]
 #[mutants::skip]
 mod tests {
     use near_sdk::test_utils::test_env::alice;
+    use sweat_jar_model::{Timezone, MS_IN_YEAR};
 
     use super::*;
-    use crate::{common::tests::Context, jar::model::Jar, test_utils::admin};
+    use crate::{
+        common::{test_data::set_test_future_success, tests::Context},
+        jar::model::Jar,
+        product::model::Product,
+        test_utils::{admin, expect_panic, UnwrapPromise, PRINCIPAL, PRODUCT},
+    };
+
+    #[test]
+    fn preview_account_migration() {
+        let product = Product::new();
+        let jars = [Jar::new(0).principal(PRINCIPAL), Jar::new(1).principal(PRINCIPAL)];
+
+        let mut context = Context::new(admin()).with_products(&[product]).with_jars(&jars);
+        context.set_block_timestamp_in_ms(MS_IN_YEAR);
+
+        let preview = context.contract().preview_account_migration(alice());
+
+        assert!(preview.blockers.is_empty());
+        assert_eq!(preview.principal.0, 2 * PRINCIPAL);
+        assert_eq!(preview.interest.0, 240_000);
+
+        let account = preview.account.unwrap();
+        assert_eq!(account.jars[PRODUCT].deposits.len(), 2);
+        assert_eq!(account.jars[PRODUCT].cache.unwrap().interest, 240_000);
+
+        assert!(!context.contract().is_account_locked(alice()));
+    }
+
+    #[test]
+    fn migrate_account_stores_receipt() {
+        let product = Product::new();
+        let jars = [Jar::new(0).principal(PRINCIPAL), Jar::new(1).principal(PRINCIPAL)];
+
+        let mut context = Context::new(admin()).with_products(&[product]).with_jars(&jars);
+        context.set_block_timestamp_in_ms(MS_IN_YEAR);
+        context.switch_account(alice());
+
+        let preview = context.contract().preview_account_migration(alice());
+
+        context.contract().migrate_account();
+
+        let receipt = context.contract().get_migration_receipt(alice()).unwrap();
+
+        assert_eq!(receipt.principal, preview.principal);
+        assert_eq!(receipt.interest, preview.interest);
+        assert_eq!(receipt.jar_ids, vec![U32(0), U32(1)]);
+        assert_eq!(receipt.migrated_at.0, MS_IN_YEAR);
+        assert_eq!(
+            receipt.target_contract,
+            context.contract().migration.new_version_account_id
+        );
+        assert!(!context.contract().has_account(&alice()));
+    }
+
+    #[test]
+    fn failed_account_migration_has_no_receipt() {
+        let mut context = Context::new(admin())
+            .with_products(&[Product::new()])
+            .with_jars(&[Jar::new(0).principal(PRINCIPAL)]);
+        context.switch_account(alice());
+
+        set_test_future_success(false);
+        context.contract().migrate_account();
+
+        assert!(context.contract().get_migration_receipt(alice()).is_none());
+        assert!(context.contract().has_account(&alice()));
+    }
+
+    #[test]
+    fn migrate_products_in_chunks() {
+        let mut context = Context::new(admin()).with_products(&[
+            Product::new().id("product_1"),
+            Product::new().id("product_2"),
+            Product::new().id("product_3"),
+        ]);
+        context.switch_account(admin());
+
+        let migrated = context
+            .contract()
+            .migrate_products(Some(vec!["product_2".to_string()]))
+            .unwrap();
+        assert_eq!(migrated, vec!["product_2".to_string()]);
+
+        assert_eq!(
+            context.contract().get_products_migration(),
+            ProductsMigrationView {
+                migrated: vec!["product_2".to_string()],
+                pending: vec!["product_1".to_string(), "product_3".to_string()],
+            }
+        );
+
+        expect_panic(&context, "Product 'product_2' is already migrated", || {
+            context.contract().migrate_products(Some(vec!["product_2".to_string()]));
+        });
+
+        let mut migrated = context.contract().migrate_products(None).unwrap();
+        migrated.sort();
+        assert_eq!(migrated, vec!["product_1".to_string(), "product_3".to_string()]);
+        assert!(context.contract().get_products_migration().pending.is_empty());
+
+        expect_panic(&context, "There are no products to migrate", || {
+            context.contract().migrate_products(None);
+        });
+    }
+
+    #[test]
+    fn failed_products_migration_is_not_recorded() {
+        let mut context = Context::new(admin()).with_products(&[Product::new()]);
+        context.switch_account(admin());
+
+        set_test_future_success(false);
+
+        let migrated = context.contract().migrate_products(None).unwrap();
+        assert!(migrated.is_empty());
+        assert!(!context.contract().migrating_products.contains(&PRODUCT.to_string()));
+
+        assert_eq!(
+            context.contract().get_products_migration().pending,
+            vec![PRODUCT.to_string()]
+        );
+
+        set_test_future_success(true);
+
+        let migrated = context.contract().migrate_products(None).unwrap();
+        assert_eq!(migrated, vec![PRODUCT.to_string()]);
+    }
+
+    #[test]
+    fn products_in_flight_are_not_sent_again() {
+        let mut context =
+            Context::new(admin()).with_products(&[Product::new().id("product_1"), Product::new().id("product_2")]);
+        context.switch_account(admin());
+
+        context.contract().migrating_products.insert("product_1".to_string());
+
+        expect_panic(&context, "Product 'product_1' is already being migrated", || {
+            context.contract().migrate_products(Some(vec!["product_1".to_string()]));
+        });
+
+        let migrated = context.contract().migrate_products(None).unwrap();
+        assert_eq!(migrated, vec!["product_2".to_string()]);
+    }
+
+    #[test]
+    fn preview_blocked_account_migration() {
+        let product = Product::new();
+        let jars = [
+            Jar::new(0).principal(PRINCIPAL).pending_withdraw(),
+            Jar::new(1).principal(PRINCIPAL).product_id("missing_product"),
+        ];
+
+        let context = Context::new(admin()).with_products(&[product]).with_jars(&jars);
+
+        let preview = context.contract().preview_account_migration(alice());
+
+        assert_eq!(
+            preview.blockers,
+            vec![
+                MigrationBlocker::JarIsLocked(U32(0)),
+                MigrationBlocker::MissingProduct("missing_product".to_string()),
+            ]
+        );
+        assert!(preview.account.is_none());
+        assert_eq!(preview.principal.0, 2 * PRINCIPAL);
+
+        let preview = context.contract().preview_account_migration(admin());
+        assert_eq!(preview.blockers, vec![MigrationBlocker::NoJars]);
+    }
+
+    #[test]
+    fn score_above_u16_blocks_account_migration() {
+        let mut context = Context::new(admin())
+            .with_products(&[Product::new()])
+            .with_jars(&[Jar::new(0).principal(PRINCIPAL)]);
+
+        context.contract().accounts.get_mut(&alice()).unwrap().score = crate::score::AccountScore {
+            scores_history: [70_000, 0],
+            ..crate::score::AccountScore::new(Timezone::hour_shift(0))
+        };
+
+        let preview = context.contract().preview_account_migration(alice());
+
+        assert_eq!(preview.blockers, vec![MigrationBlocker::UnsupportedScore(70_000)]);
+        assert!(preview.account.is_none());
+
+        context.switch_account(alice());
+
+        expect_panic(&context, "Score 70000 doesn't fit into v2 account", || {
+            context.contract().migrate_account();
+        });
+
+        assert!(context.contract().has_account(&alice()));
+    }
 
     #[test]
     #[ignore]
@@ -326,8 +655,8 @@ mod tests {
             .create_jars(alice.clone(), "product_4".to_string(), 1 * 10u128.pow(18), 10);
 
         context.switch_account(alice.clone());
-        let (principal, memo, msg) = context.contract().prepare_migration_params(alice.clone()).unwrap();
-        println!("principal: {principal}");
+        let (receipt, memo, msg) = context.contract().prepare_migration_params(alice.clone()).unwrap();
+        println!("principal: {}", receipt.principal);
         println!("memo: {memo}");
         println!("msg: {msg}");
     }
@@ -530,6 +859,13 @@ mod product_v2 {
                 withdrawal_fee: value.withdrawal_fee.map(|fee| match fee {
                     WithdrawalFeeLegacy::Fix(amount) => WithdrawalFee::Fix(amount.into()),
                     WithdrawalFeeLegacy::Percent(percentage) => WithdrawalFee::Percent(percentage.into()),
+                    // The new contract doesn't support fee schedules, so the initial fee of the schedule is kept
+                    WithdrawalFeeLegacy::Schedule(steps) => WithdrawalFee::Percent(
+                        steps
+                            .first()
+                            .map_or_else(UDecimalLegacy::default, |step| step.fee)
+                            .into(),
+                    ),
                 }),
                 public_key: value.public_key.map(Into::into),
                 is_enabled: value.is_enabled,
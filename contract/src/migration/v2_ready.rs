@@ -0,0 +1,129 @@
+use near_sdk::{
+    collections::UnorderedMap,
+    env::{self, panic_str},
+    near,
+    store::{LookupMap, LookupSet},
+    AccountId, PanicOnDefault,
+};
+use sweat_jar_model::{jar::JarId, ProductId};
+
+use super::{
+    account_jars_non_versioned::AccountJarsNonVersioned,
+    registry::MigrationArgs,
+    streaks::{ContractBeforeStreaks, ProductBeforeStreaks},
+};
+use crate::{
+    jar::{account::versioned::Account, model::AccountJarsLegacy},
+    MigrationState, StorageKey,
+};
+
+/// Contract layout before accounts migration to v2 contract was configured.
+/// It used to be migrated with a separate `migrate_state_to_v2_ready` init method.
+#[near]
+#[derive(PanicOnDefault)]
+pub struct ContractBeforeMigration {
+    pub token_account_id: AccountId,
+    pub fee_account_id: AccountId,
+    pub manager: AccountId,
+    pub products: UnorderedMap<ProductId, ProductBeforeStreaks>,
+    pub last_jar_id: JarId,
+    pub accounts: LookupMap<AccountId, Account>,
+    pub account_jars_non_versioned: LookupMap<AccountId, AccountJarsNonVersioned>,
+    pub account_jars_v1: LookupMap<AccountId, AccountJarsLegacy>,
+}
+
+/// Configures the v2 contract accounts are migrated to.
+pub(super) fn migrate_state(args: &MigrationArgs) {
+    let old_state: ContractBeforeMigration = env::state_read().expect("Failed to extract old contract state.");
+
+    let new_version_account_id = args
+        .new_version_account_id
+        .clone()
+        .unwrap_or_else(|| panic_str("New version account id is required to migrate from version 0"));
+
+    env::state_write(&ContractBeforeStreaks {
+        token_account_id: old_state.token_account_id,
+        fee_account_id: old_state.fee_account_id,
+        manager: old_state.manager,
+        products: old_state.products,
+        last_jar_id: old_state.last_jar_id,
+        accounts: old_state.accounts,
+        account_jars_non_versioned: old_state.account_jars_non_versioned,
+        account_jars_v1: old_state.account_jars_v1,
+        migration: MigrationState {
+            new_version_account_id,
+            migrating_accounts: LookupSet::new(StorageKey::Migration),
+        },
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{collections::UnorderedMap, store::LookupMap, test_utils::test_env::bob};
+    use sweat_jar_model::{api::StateMigration, UDecimal};
+
+    use super::ContractBeforeMigration;
+    use crate::{
+        common::tests::Context,
+        migration::{registry::write_state_fixture, streaks::ProductBeforeStreaks},
+        product::model::{Apy, Cap, Terms},
+        test_utils::{admin, expect_panic, PRODUCT},
+        Contract, StorageKey,
+    };
+
+    fn state_before_migration() -> ContractBeforeMigration {
+        let mut products = UnorderedMap::new(StorageKey::_ProductsLegacyV3);
+        products.insert(
+            &PRODUCT.to_string(),
+            &ProductBeforeStreaks {
+                id: PRODUCT.to_string(),
+                apy: Apy::Constant(UDecimal::new(12, 2)),
+                cap: Cap { min: 10, max: 100 },
+                terms: Terms::Flexible,
+                withdrawal_fee: None,
+                public_key: None,
+                is_enabled: true,
+                score_cap: 0,
+            },
+        );
+
+        ContractBeforeMigration {
+            token_account_id: "token".parse().unwrap(),
+            fee_account_id: "fee".parse().unwrap(),
+            manager: admin(),
+            products,
+            last_jar_id: 10,
+            accounts: LookupMap::new(StorageKey::Accounts),
+            account_jars_non_versioned: LookupMap::new(StorageKey::AccountsLegacyV2),
+            account_jars_v1: LookupMap::new(StorageKey::AccountsLegacyV1),
+        }
+    }
+
+    #[test]
+    fn migrate_state_before_v2_ready() {
+        let _ctx = Context::new(admin());
+
+        write_state_fixture(&state_before_migration(), 0);
+
+        let contract = Contract::migrate_state(None, Some(bob()));
+
+        assert_eq!(contract.migration.new_version_account_id, bob());
+        assert_eq!(contract.last_jar_id, 10);
+        assert_eq!(contract.get_product(&PRODUCT.to_string()).cap.max, 100);
+    }
+
+    #[test]
+    fn migrate_state_before_v2_ready_without_new_version_account() {
+        let ctx = Context::new(admin());
+
+        write_state_fixture(&state_before_migration(), 0);
+
+        expect_panic(
+            &ctx,
+            "New version account id is required to migrate from version 0",
+            || {
+                Contract::migrate_state(None, None);
+            },
+        );
+    }
+}
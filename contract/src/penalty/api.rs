@@ -4,11 +4,11 @@ use sweat_jar_model::{api::PenaltyApi, jar::JarIdView};
 use crate::{
     event::{
         emit, BatchPenaltyData,
-        EventKind::{ApplyPenalty, BatchApplyPenalty},
+        EventKind::{ApplyPenalty, ApplyScorePenalty, BatchApplyPenalty, BatchApplyScorePenalty},
         PenaltyData,
     },
-    product::model::Apy,
-    Contract, ContractExt, JarsStorage,
+    product::model::{Apy, Product},
+    Contract, ContractExt, JarsStorage, ProductsStorage,
 };
 
 #[near_bindgen]
@@ -23,28 +23,47 @@ impl PenaltyApi for Contract {
         let product = self.get_product(&jar.product_id).clone();
         let now = env::block_timestamp_ms();
 
-        assert_penalty_apy(&product.apy);
+        assert_penalty_applicable(&product);
+
+        if product.is_score_product() {
+            self.settle_score_interest(&account_id, now);
+        }
 
         self.get_jar_mut_internal(&account_id, jar_id)
             .apply_penalty(&product, value, now);
 
-        emit(ApplyPenalty(PenaltyData {
+        let data = PenaltyData {
             id: jar_id,
             is_applied: value,
             timestamp: now,
-        }));
+        };
+
+        if product.is_score_product() {
+            emit(ApplyScorePenalty(data));
+        } else {
+            emit(ApplyPenalty(data));
+        }
     }
 
     fn batch_set_penalty(&mut self, jars: Vec<(AccountId, Vec<JarIdView>)>, value: bool) {
         self.assert_manager();
 
         let mut applied_jars = vec![];
+        let mut applied_score_jars = vec![];
 
         let now = env::block_timestamp_ms();
 
         for (account_id, jars) in jars {
             self.migrate_account_if_needed(&account_id);
 
+            if self
+                .accounts
+                .get(&account_id)
+                .is_some_and(|account_jars| account_jars.has_score_jars())
+            {
+                self.settle_score_interest(&account_id, now);
+            }
+
             let account_jars = self
                 .accounts
                 .get_mut(&account_id)
@@ -55,15 +74,16 @@ impl PenaltyApi for Contract {
 
                 let jar = account_jars.get_jar_mut(jar_id);
 
-                let product = self
-                    .products
-                    .get(&jar.product_id)
-                    .unwrap_or_else(|| env::panic_str(&format!("Product '{}' doesn't exist", jar.product_id)));
+                let product = self.products.get_product(&jar.product_id);
 
-                assert_penalty_apy(&product.apy);
-                jar.apply_penalty(&product, value, now);
+                assert_penalty_applicable(product);
+                jar.apply_penalty(product, value, now);
 
-                applied_jars.push(jar_id);
+                if product.is_score_product() {
+                    applied_score_jars.push(jar_id);
+                } else {
+                    applied_jars.push(jar_id);
+                }
             }
         }
 
@@ -72,11 +92,24 @@ impl PenaltyApi for Contract {
             is_applied: value,
             timestamp: now,
         }));
+
+        if !applied_score_jars.is_empty() {
+            emit(BatchApplyScorePenalty(BatchPenaltyData {
+                jars: applied_score_jars,
+                is_applied: value,
+                timestamp: now,
+            }));
+        }
     }
 }
 
-fn assert_penalty_apy(apy: &Apy) {
-    match apy {
+/// Score based products don't have APY, so the penalty for them is not limited by APY type.
+fn assert_penalty_applicable(product: &Product) {
+    if product.is_score_product() {
+        return;
+    }
+
+    match product.apy {
         Apy::Constant(_) => env::panic_str("Penalty is not applicable for constant APY"),
         Apy::Downgradable(_) => (),
     }
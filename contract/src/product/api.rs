@@ -30,14 +30,34 @@ impl ProductApi for Contract {
 
             assert!(apy.is_zero(), "Step based products do not support constant APY");
 
-            if let Terms::Fixed(fixed) = &product.terms {
-                assert!(!fixed.allows_top_up, "Step based products don't support top up");
+            if let Some(streak) = &product.streak {
+                assert!(streak.threshold > 0, "Streak threshold must be positive");
+
+                // An account has a single streak, so it must mean the same for all of its jars.
+                assert!(
+                    self.products
+                        .values()
+                        .filter_map(|existing| existing.streak.as_ref())
+                        .all(|existing| existing.threshold == streak.threshold),
+                    "All products with streak bonus must have the same threshold"
+                );
             }
+        } else {
+            assert!(
+                product.streak.is_none(),
+                "Streak bonus is supported only for step based products"
+            );
         }
 
+        assert!(
+            product.unbonding_period.is_none() || product.is_flexible(),
+            "Unbonding period is supported only for Flexible products"
+        );
+
         product.assert_fee_amount();
+        product.assert_fee_split();
 
-        self.products.insert(&product.id, &product);
+        self.products.insert(product.id.clone(), product.clone().into());
 
         emit(EventKind::RegisterProduct(product));
     }
@@ -47,13 +67,13 @@ impl ProductApi for Contract {
         self.assert_manager();
         assert_one_yocto();
 
-        let mut product = self.get_product(&product_id);
+        let mut product = self.get_product(&product_id).clone();
 
         require!(is_enabled != product.is_enabled, "Status matches");
 
         product.is_enabled = is_enabled;
 
-        self.products.insert(&product_id, &product);
+        self.products.insert(product_id.clone(), product.into());
 
         emit(EventKind::EnableProduct(EnableProductData {
             id: product_id,
@@ -66,9 +86,9 @@ impl ProductApi for Contract {
         self.assert_manager();
         assert_one_yocto();
 
-        let mut product = self.get_product(&product_id);
+        let mut product = self.get_product(&product_id).clone();
         product.public_key = Some(public_key.0.clone());
-        self.products.insert(&product_id, &product);
+        self.products.insert(product_id.clone(), product.into());
 
         emit(EventKind::ChangeProductPublicKey(ChangeProductPublicKeyData {
             product_id,
@@ -77,6 +97,9 @@ impl ProductApi for Contract {
     }
 
     fn get_products(&self) -> Vec<ProductView> {
-        self.products.values().map(|product| product.clone().into()).collect()
+        self.products
+            .values()
+            .map(|product| Product::clone(product).into())
+            .collect()
     }
 }
@@ -1,9 +1,11 @@
 use sweat_jar_model::{
-    product::{RegisterProductCommand, TermsDto, WithdrawalFeeDto},
+    product::{FeeShareDto, RegisterProductCommand, StreakBonusDto, TermsDto, WithdrawalFeeDto},
     UDecimal,
 };
 
-use crate::product::model::{Apy, Cap, DowngradableApy, FixedProductTerms, Product, Terms, WithdrawalFee};
+use crate::product::model::{
+    Apy, Cap, DowngradableApy, FeeScheduleStep, FeeShare, FixedProductTerms, Product, StreakBonus, Terms, WithdrawalFee,
+};
 
 impl From<RegisterProductCommand> for Product {
     fn from(value: RegisterProductCommand) -> Self {
@@ -20,6 +22,15 @@ impl From<RegisterProductCommand> for Product {
             WithdrawalFeeDto::Percent(significand, exponent) => {
                 WithdrawalFee::Percent(UDecimal::new(significand.0, exponent))
             }
+            WithdrawalFeeDto::Schedule(steps) => WithdrawalFee::Schedule(
+                steps
+                    .into_iter()
+                    .map(|step| FeeScheduleStep {
+                        until: step.until.0,
+                        fee: UDecimal::new(step.fee.0 .0, step.fee.1),
+                    })
+                    .collect(),
+            ),
         });
 
         Self {
@@ -34,6 +45,28 @@ impl From<RegisterProductCommand> for Product {
             public_key: value.public_key.map(|key| key.0),
             is_enabled: value.is_enabled,
             score_cap: value.score_cap,
+            streak: value.streak.map(Into::into),
+            unbonding_period: value.unbonding_period.map(|period| period.0),
+            fee_split: value.fee_split.map(|split| split.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<StreakBonusDto> for StreakBonus {
+    fn from(value: StreakBonusDto) -> Self {
+        Self {
+            threshold: value.threshold,
+            step: UDecimal::new(value.step.0 .0, value.step.1),
+            max_days: value.max_days,
+        }
+    }
+}
+
+impl From<FeeShareDto> for FeeShare {
+    fn from(value: FeeShareDto) -> Self {
+        Self {
+            beneficiary_id: value.beneficiary_id,
+            share: UDecimal::new(value.share.0 .0, value.share.1),
         }
     }
 }
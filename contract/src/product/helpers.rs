@@ -11,7 +11,7 @@ use sweat_jar_model::{Score, TokenAmount, UDecimal, MS_IN_YEAR};
 use crate::{
     common::{tests::Context, Duration},
     jar::model::JarTicket,
-    product::model::{Apy, Cap, FixedProductTerms, Product, Terms, WithdrawalFee},
+    product::model::{Apy, Cap, FeeShare, FixedProductTerms, Product, StreakBonus, Terms, WithdrawalFee},
     test_utils::PRODUCT,
     Contract,
 };
@@ -58,6 +58,9 @@ impl Product {
             public_key: None,
             is_enabled: true,
             score_cap: 0,
+            streak: None,
+            unbonding_period: None,
+            fee_split: None,
         }
     }
 }
@@ -147,6 +150,28 @@ impl Product {
         self.score_cap = cap;
         self
     }
+
+    pub(crate) fn streak(mut self, streak: StreakBonus) -> Self {
+        self.streak = Some(streak);
+        self
+    }
+
+    pub(crate) fn unbonding_period(mut self, period: Duration) -> Self {
+        self.unbonding_period = Some(period);
+        self
+    }
+
+    pub(crate) fn fee_split(mut self, split: &[(&str, UDecimal)]) -> Self {
+        self.fee_split = split
+            .iter()
+            .map(|(beneficiary_id, share)| FeeShare {
+                beneficiary_id: beneficiary_id.parse().unwrap(),
+                share: *share,
+            })
+            .collect::<Vec<_>>()
+            .into();
+        self
+    }
 }
 
 impl Context {
@@ -3,4 +3,5 @@ pub mod command;
 pub mod helpers;
 pub mod model;
 pub mod tests;
+pub mod versioned;
 pub mod view;
@@ -1,7 +1,13 @@
-use near_sdk::{near, require};
-use sweat_jar_model::{ProductId, Score, ToAPY, TokenAmount, UDecimal};
+use near_sdk::{near, require, AccountId};
+use sweat_jar_model::{
+    withdraw::{Fee, FeeTransfer},
+    ProductId, Score, ToAPY, TokenAmount, UDecimal,
+};
 
-use crate::{common::Duration, env};
+use crate::{
+    common::{to_hex, Duration},
+    env,
+};
 
 /// The `Product` struct describes the terms of a deposit jar. It can be of Flexible or Fixed type.
 #[near(serializers=[borsh, json])]
@@ -30,6 +36,17 @@ pub struct Product {
 
     /// TODO: document 0 - non step jar
     pub score_cap: Score,
+
+    /// An optional APY bonus for consecutive days of reaching a score threshold. Applicable only for step products.
+    pub streak: Option<StreakBonus>,
+
+    /// An optional period between a withdrawal request and the moment tokens can be transferred to the user.
+    /// Applicable only for Flexible products.
+    pub unbonding_period: Option<Duration>,
+
+    /// An optional distribution of the withdrawal fee between several beneficiaries.
+    /// If it's not set, the whole fee goes to the contract's fee account.
+    pub fee_split: Option<Vec<FeeShare>>,
 }
 
 /// The `Terms` enum describes additional terms specific to either Flexible or Fixed products.
@@ -59,7 +76,8 @@ pub struct FixedProductTerms {
     pub allows_restaking: bool,
 }
 
-/// The `WithdrawalFee` enum describes withdrawal fee details, which can be either a fixed amount or a percentage of the withdrawal.
+/// The `WithdrawalFee` enum describes withdrawal fee details, which can be either a fixed amount, a percentage of the withdrawal
+/// or a percentage decaying with the time the jar was held.
 #[near(serializers=[borsh, json])]
 #[derive(Clone, Debug, PartialEq)]
 #[serde(tag = "type", content = "data", rename_all = "snake_case")]
@@ -69,6 +87,75 @@ pub enum WithdrawalFee {
 
     /// Describes a percentage of the withdrawal amount that a user must pay as a fee on withdrawal.
     Percent(UDecimal),
+
+    /// Describes a percentage of the withdrawal amount that depends on how long the jar was held.
+    /// There is no fee after the last step.
+    Schedule(Vec<FeeScheduleStep>),
+}
+
+/// The `FeeScheduleStep` struct describes a withdrawal fee applied until the jar reaches a certain age.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeScheduleStep {
+    /// The age of the jar in milliseconds since its creation until which the fee is applied.
+    pub until: Duration,
+
+    /// The percentage of the withdrawal amount.
+    pub fee: UDecimal,
+}
+
+impl WithdrawalFee {
+    /// Returns the fee for withdrawing `amount` from a jar held for `held` milliseconds.
+    pub(crate) fn get_amount(&self, amount: TokenAmount, held: Duration) -> TokenAmount {
+        match self {
+            WithdrawalFee::Fix(fee) => *fee,
+            WithdrawalFee::Percent(percent) => percent * amount,
+            WithdrawalFee::Schedule(steps) => steps
+                .iter()
+                .find(|step| held < step.until)
+                .map_or(0, |step| step.fee * amount),
+        }
+    }
+
+    /// Returns the fee for a withdrawal from a jar held for `held` milliseconds that leaves `net` tokens
+    /// after the fee is deducted. It's the inverse of `get_amount`, so a percentage fee isn't charged on itself.
+    pub(crate) fn get_amount_for_net(&self, net: TokenAmount, held: Duration) -> TokenAmount {
+        if net == 0 {
+            return 0;
+        }
+
+        match self {
+            WithdrawalFee::Fix(fee) => *fee,
+            WithdrawalFee::Percent(percent) => fee_for_net(percent, net),
+            WithdrawalFee::Schedule(steps) => steps
+                .iter()
+                .find(|step| held < step.until)
+                .map_or(0, |step| fee_for_net(&step.fee, net)),
+        }
+    }
+}
+
+/// Solves `fee = rate * (net + fee)` for `fee`. A rate of 100% or more takes everything,
+/// so the result is unbounded and the caller caps it by the withheld fee.
+fn fee_for_net(rate: &UDecimal, net: TokenAmount) -> TokenAmount {
+    let denominator = 10u128.pow(rate.exponent).saturating_sub(rate.significand);
+
+    if denominator == 0 {
+        return TokenAmount::MAX;
+    }
+
+    net * rate.significand / denominator
+}
+
+/// The `FeeShare` struct describes a share of the withdrawal fee that goes to a beneficiary.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeShare {
+    /// The account receiving this share of the fee.
+    pub beneficiary_id: AccountId,
+
+    /// The share of the fee, from 0 to 1.
+    pub share: UDecimal,
 }
 
 /// The `Apy` enum describes the Annual Percentage Yield (APY) of the product, which can be either constant or downgradable.
@@ -94,6 +181,22 @@ pub struct DowngradableApy {
     pub fallback: UDecimal,
 }
 
+/// The `StreakBonus` struct describes how consecutive days of walking raise score based APY.
+/// Every day of a streak adds `step` share of score based APY, up to `max_days` streak days.
+/// A day missing the `threshold` resets the streak.
+#[near(serializers=[borsh, json])]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreakBonus {
+    /// Daily score required to continue a streak.
+    pub threshold: Score,
+
+    /// APY bonus for each day of a streak relative to score based APY.
+    pub step: UDecimal,
+
+    /// Number of streak days after which the bonus stops growing.
+    pub max_days: u16,
+}
+
 /// The `Cap` struct defines the capacity of a deposit jar in terms of the minimum and maximum allowed principal amounts.
 #[near(serializers=[borsh, json])]
 #[derive(Clone, Debug)]
@@ -110,11 +213,45 @@ impl Product {
         self.score_cap > 0
     }
 
+    pub(crate) fn get_lockup_term(&self) -> Option<Duration> {
+        match self.clone().terms {
+            Terms::Fixed(value) => Some(value.lockup_term),
+            Terms::Flexible => None,
+        }
+    }
+
+    /// Hex encoded sha256 of the product. Enabling or disabling the product doesn't change its terms,
+    /// so it doesn't affect the hash.
+    pub(crate) fn terms_hash(&self) -> String {
+        let terms = Self {
+            is_enabled: true,
+            ..self.clone()
+        };
+
+        to_hex(&env::sha256_array(
+            &near_sdk::borsh::to_vec(&terms).expect("Failed to serialize product"),
+        ))
+    }
+
     pub(crate) fn apy_for_score(&self, score: &[Score]) -> UDecimal {
-        let total_score: Score = score.iter().map(|score| score.min(&self.score_cap)).sum();
+        let total_score = score
+            .iter()
+            .map(|score| score.min(&self.score_cap))
+            .fold(0, |total: Score, score| total.saturating_add(*score));
         total_score.to_apy()
     }
 
+    /// Raises score based APY by a streak bonus if the product has one.
+    pub(crate) fn apy_with_streak(&self, apy: UDecimal, streak: u16) -> UDecimal {
+        let Some(bonus) = &self.streak else {
+            return apy;
+        };
+
+        let days = streak.min(bonus.max_days);
+
+        apy + apy * UDecimal::new(bonus.step.significand * u128::from(days), bonus.step.exponent)
+    }
+
     pub(crate) fn is_flexible(&self) -> bool {
         self.terms == Terms::Flexible
     }
@@ -156,21 +293,74 @@ impl Product {
         let fee_ok = match fee {
             WithdrawalFee::Fix(amount) => amount < &self.cap.min,
             WithdrawalFee::Percent(percent) => percent.to_f32() < 100.0,
+            WithdrawalFee::Schedule(steps) => steps.iter().all(|step| step.fee.to_f32() < 100.0),
         };
 
         require!(
             fee_ok,
             "Fee for this product is too high. It is possible for customer to pay more in fees than he staked."
         );
-    }
-}
 
-#[cfg(test)]
-impl Product {
-    pub(crate) fn get_lockup_term(&self) -> Option<Duration> {
-        match self.clone().terms {
-            Terms::Fixed(value) => Some(value.lockup_term),
-            Terms::Flexible => None,
+        if let WithdrawalFee::Schedule(steps) = fee {
+            require!(!steps.is_empty(), "Fee schedule must have at least one step");
+            require!(
+                steps.first().is_some_and(|step| step.until > 0)
+                    && steps.windows(2).all(|pair| pair[0].until < pair[1].until),
+                "Fee schedule steps must be in ascending order"
+            );
         }
     }
+
+    /// Check if fee split in new product is consistent
+    pub(crate) fn assert_fee_split(&self) {
+        let Some(ref split) = self.fee_split else {
+            return;
+        };
+
+        require!(self.withdrawal_fee.is_some(), "Fee split requires withdrawal fee");
+        require!(split.len() > 1, "Fee split must have at least two beneficiaries");
+        require!(
+            split.iter().all(|share| !share.share.is_zero()),
+            "Fee share must be positive"
+        );
+
+        let total = split
+            .iter()
+            .fold(UDecimal::default(), |total, share| total + share.share);
+
+        require!(
+            total.significand == 10u128.pow(total.exponent),
+            "Fee shares must add up to 1"
+        );
+    }
+
+    /// Distributes `amount` of withdrawal fee between beneficiaries of the product.
+    /// The last beneficiary receives the rounding remainder, so the whole amount is always distributed.
+    pub(crate) fn split_fee(&self, fee_account_id: &AccountId, amount: TokenAmount) -> Fee {
+        let Some(ref split) = self.fee_split else {
+            return Fee::new(fee_account_id.clone(), amount);
+        };
+
+        let mut remainder = amount;
+
+        let transfers = split
+            .iter()
+            .enumerate()
+            .map(|(index, share)| {
+                let part = if index == split.len() - 1 {
+                    remainder
+                } else {
+                    share.share * amount
+                };
+                remainder -= part;
+
+                FeeTransfer {
+                    beneficiary_id: share.beneficiary_id.clone(),
+                    amount: part.into(),
+                }
+            })
+            .collect();
+
+        Fee { transfers }
+    }
 }
@@ -1,23 +1,26 @@
 #![cfg(test)]
 
 use near_sdk::{
+    borsh::{from_slice, to_vec},
     json_types::{Base64VecU8, U128, U64},
     test_utils::test_env::alice,
 };
 use sweat_jar_model::{
     api::ProductApi,
     product::{
-        ApyView, DowngradableApyView, FixedProductTermsDto, ProductView, RegisterProductCommand, TermsDto, TermsView,
-        WithdrawalFeeDto, WithdrawalFeeView,
+        ApyView, DowngradableApyView, FeeScheduleStepDto, FeeScheduleStepView, FeeShareDto, FeeShareView,
+        FixedProductTermsDto, ProductView, RegisterProductCommand, TermsDto, TermsView, WithdrawalFeeDto,
+        WithdrawalFeeView,
     },
-    UDecimal, MS_IN_YEAR,
+    UDecimal, MS_IN_DAY, MS_IN_YEAR,
 };
 
 use crate::{
     common::tests::Context,
     product::{
         helpers::MessageSigner,
-        model::{Apy, DowngradableApy, Product, Terms, WithdrawalFee},
+        model::{Apy, DowngradableApy, FeeScheduleStep, Product, Terms, WithdrawalFee},
+        versioned::ProductVersioned,
     },
     test_utils::admin,
 };
@@ -36,7 +39,7 @@ fn disable_product_when_enabled() {
 
     let mut context = Context::new(admin.clone()).with_products(&[product.clone()]);
 
-    let mut product = context.contract().get_product(&product.id);
+    let mut product = context.contract().get_product(&product.id).clone();
     assert!(product.is_enabled);
 
     context.switch_account(&admin);
@@ -44,9 +47,7 @@ fn disable_product_when_enabled() {
         context.contract().set_enabled(product.id.to_string(), false)
     });
 
-    context.contract().products_cache.borrow_mut().clear();
-
-    product = context.contract().get_product(&product.id);
+    product = context.contract().get_product(&product.id).clone();
     assert!(!product.is_enabled);
 }
 
@@ -58,7 +59,7 @@ fn enable_product_when_enabled() {
 
     let mut context = Context::new(admin.clone()).with_products(&[product.clone()]);
 
-    let product = context.contract().get_product(&product.id);
+    let product = context.contract().get_product(&product.id).clone();
     assert!(product.is_enabled);
 
     context.switch_account(&admin);
@@ -95,7 +96,7 @@ fn register_product(command: RegisterProductCommand) -> (Product, ProductView) {
     context.switch_account(&admin);
     context.with_deposit_yocto(1, |context| context.contract().register_product(command));
 
-    let product = context.contract().products.into_iter().last().unwrap().1.clone();
+    let product = context.contract().products.into_iter().last().unwrap().1.clone().into();
     let view = context.contract().get_products().first().unwrap().clone();
 
     (product, view)
@@ -190,6 +191,161 @@ fn register_product_with_fee() {
     assert_eq!(view.withdrawal_fee, Some(WithdrawalFeeView::Percent(0.12)));
 }
 
+#[test]
+#[should_panic(expected = "Unbonding period is supported only for Flexible products")]
+fn register_fixed_product_with_unbonding_period() {
+    register_product(RegisterProductCommand {
+        id: "fixed_product_with_unbonding".to_string(),
+        unbonding_period: Some(U64(MS_IN_YEAR)),
+        ..Default::default()
+    });
+}
+
+#[test]
+fn register_product_with_unbonding_period() {
+    let (product, view) = register_product(RegisterProductCommand {
+        id: "product_with_unbonding".to_string(),
+        terms: TermsDto::Flexible,
+        unbonding_period: Some(U64(MS_IN_YEAR)),
+        ..Default::default()
+    });
+
+    assert_eq!(product.unbonding_period, Some(MS_IN_YEAR));
+    assert_eq!(view.unbonding_period, Some(U64(MS_IN_YEAR)));
+}
+
+fn fee_schedule(steps: &[(u64, u128)]) -> Option<WithdrawalFeeDto> {
+    WithdrawalFeeDto::Schedule(
+        steps
+            .iter()
+            .map(|(until, fee)| FeeScheduleStepDto {
+                until: U64(*until),
+                fee: (U128(*fee), 2),
+            })
+            .collect(),
+    )
+    .into()
+}
+
+#[test]
+fn register_product_with_fee_schedule() {
+    let (product, view) = register_product(RegisterProductCommand {
+        id: "product_with_fee_schedule".to_string(),
+        withdrawal_fee: fee_schedule(&[(MS_IN_DAY * 30, 5), (MS_IN_DAY * 90, 1)]),
+        ..Default::default()
+    });
+
+    assert_eq!(
+        product.withdrawal_fee,
+        Some(WithdrawalFee::Schedule(vec![
+            FeeScheduleStep {
+                until: MS_IN_DAY * 30,
+                fee: UDecimal::new(5, 2),
+            },
+            FeeScheduleStep {
+                until: MS_IN_DAY * 90,
+                fee: UDecimal::new(1, 2),
+            },
+        ]))
+    );
+
+    assert_eq!(
+        view.withdrawal_fee,
+        Some(WithdrawalFeeView::Schedule(vec![
+            FeeScheduleStepView {
+                until: U64(MS_IN_DAY * 30),
+                fee: 0.05,
+            },
+            FeeScheduleStepView {
+                until: U64(MS_IN_DAY * 90),
+                fee: 0.01,
+            },
+        ]))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Fee schedule steps must be in ascending order")]
+fn register_product_with_unordered_fee_schedule() {
+    register_product(RegisterProductCommand {
+        id: "product_with_fee_schedule".to_string(),
+        withdrawal_fee: fee_schedule(&[(MS_IN_DAY * 90, 1), (MS_IN_DAY * 30, 5)]),
+        ..Default::default()
+    });
+}
+
+#[test]
+#[should_panic(
+    expected = "Fee for this product is too high. It is possible for customer to pay more in fees than he staked."
+)]
+fn register_product_with_too_high_scheduled_fee() {
+    register_product(RegisterProductCommand {
+        id: "product_with_fee_schedule".to_string(),
+        withdrawal_fee: fee_schedule(&[(MS_IN_DAY * 30, 10_000), (MS_IN_DAY * 90, 1)]),
+        ..Default::default()
+    });
+}
+
+fn fee_split(shares: &[(&str, u128)]) -> Option<Vec<FeeShareDto>> {
+    shares
+        .iter()
+        .map(|(beneficiary_id, share)| FeeShareDto {
+            beneficiary_id: beneficiary_id.parse().unwrap(),
+            share: (U128(*share), 1),
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+#[test]
+fn register_product_with_fee_split() {
+    let (product, view) = register_product(RegisterProductCommand {
+        id: "product_with_fee_split".to_string(),
+        withdrawal_fee: WithdrawalFeeDto::Fix(U128(10)).into(),
+        fee_split: fee_split(&[("treasury", 7), ("rewards", 3)]),
+        ..Default::default()
+    });
+
+    let split = product.fee_split.unwrap();
+    assert_eq!(split.len(), 2);
+    assert_eq!(split[0].share, UDecimal::new(7, 1));
+
+    assert_eq!(
+        view.fee_split,
+        Some(vec![
+            FeeShareView {
+                beneficiary_id: "treasury".parse().unwrap(),
+                share: 0.7,
+            },
+            FeeShareView {
+                beneficiary_id: "rewards".parse().unwrap(),
+                share: 0.3,
+            },
+        ])
+    );
+}
+
+#[test]
+#[should_panic(expected = "Fee shares must add up to 1")]
+fn register_product_with_incomplete_fee_split() {
+    register_product(RegisterProductCommand {
+        id: "product_with_fee_split".to_string(),
+        withdrawal_fee: WithdrawalFeeDto::Fix(U128(10)).into(),
+        fee_split: fee_split(&[("treasury", 7), ("rewards", 2)]),
+        ..Default::default()
+    });
+}
+
+#[test]
+#[should_panic(expected = "Fee split requires withdrawal fee")]
+fn register_product_with_fee_split_without_fee() {
+    register_product(RegisterProductCommand {
+        id: "product_with_fee_split".to_string(),
+        fee_split: fee_split(&[("treasury", 7), ("rewards", 3)]),
+        ..Default::default()
+    });
+}
+
 #[test]
 fn register_product_with_flexible_terms() {
     let (product, view) = register_product(RegisterProductCommand {
@@ -220,7 +376,7 @@ fn set_public_key() {
             .set_public_key(product.id.clone(), Base64VecU8(new_pk.clone()))
     });
 
-    let product = context.contract().products.get(&product.id).unwrap();
+    let product = context.contract().get_product(&product.id).clone();
     assert_eq!(&new_pk, product.public_key.as_ref().unwrap());
 }
 
@@ -280,3 +436,19 @@ fn assert_cap_more_than_max() {
 fn generate_product() -> Product {
     Product::new().cap(100, 100_000_000_000)
 }
+
+#[test]
+fn product_is_stored_with_version_tag() {
+    let product = Product::new().flexible();
+
+    let bytes = to_vec(&ProductVersioned::from(product.clone())).unwrap();
+    assert_eq!(bytes[0], 0);
+    assert_eq!(bytes[1..], to_vec(&product).unwrap());
+
+    let restored: Product = from_slice::<ProductVersioned>(&bytes).unwrap().into();
+    assert_eq!(to_vec(&restored).unwrap(), to_vec(&product).unwrap());
+
+    let mut bytes = bytes;
+    bytes[0] = 100;
+    assert!(from_slice::<ProductVersioned>(&bytes).is_err());
+}
@@ -0,0 +1,66 @@
+use std::ops::{Deref, DerefMut};
+
+use near_sdk::borsh::{
+    io::{Error, ErrorKind::InvalidData, Read},
+    BorshDeserialize, BorshSerialize,
+};
+
+use crate::product::model::Product;
+
+/// The latest layout of the stored product. When a new field is added to `Product`,
+/// the current layout is frozen in a new variant and upgraded on read.
+pub type ProductLastVersion = Product;
+
+#[derive(Clone, Debug, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub enum ProductVersioned {
+    V1(ProductLastVersion),
+}
+
+/// Custom `BorshDeserialize` implementation is needed to automatically
+/// convert old versions to latest version
+impl BorshDeserialize for ProductVersioned {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self, Error> {
+        let tag: u8 = BorshDeserialize::deserialize_reader(reader)?;
+
+        let result = match tag {
+            0 => ProductVersioned::V1(BorshDeserialize::deserialize_reader(reader)?),
+            // Add new versions here:
+            _ => return Err(Error::new(InvalidData, format!("Unexpected variant tag: {tag:?}"))),
+        };
+
+        Ok(result)
+    }
+}
+
+impl From<ProductLastVersion> for ProductVersioned {
+    fn from(value: ProductLastVersion) -> Self {
+        ProductVersioned::V1(value)
+    }
+}
+
+impl From<ProductVersioned> for ProductLastVersion {
+    fn from(value: ProductVersioned) -> Self {
+        match value {
+            ProductVersioned::V1(product) => product,
+        }
+    }
+}
+
+impl Deref for ProductVersioned {
+    type Target = ProductLastVersion;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            ProductVersioned::V1(product) => product,
+        }
+    }
+}
+
+impl DerefMut for ProductVersioned {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            ProductVersioned::V1(product) => product,
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use near_sdk::json_types::{U128, U64};
 use sweat_jar_model::product::{
-    ApyView, CapView, DowngradableApyView, FixedProductTermsView, ProductView, TermsView, WithdrawalFeeView,
+    ApyView, CapView, DowngradableApyView, FeeScheduleStepView, FeeShareView, FixedProductTermsView, ProductView,
+    StreakBonusView, TermsView, WithdrawalFeeView,
 };
 
 use crate::{
-    product::model::{Cap, DowngradableApy, Terms, WithdrawalFee},
+    product::model::{Cap, DowngradableApy, FeeShare, StreakBonus, Terms, WithdrawalFee},
     Apy, Product,
 };
 
@@ -18,6 +19,28 @@ impl From<Product> for ProductView {
             withdrawal_fee: value.withdrawal_fee.map(Into::into),
             is_enabled: value.is_enabled,
             score_cap: value.score_cap,
+            streak: value.streak.map(Into::into),
+            unbonding_period: value.unbonding_period.map(U64),
+            fee_split: value.fee_split.map(|split| split.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<FeeShare> for FeeShareView {
+    fn from(value: FeeShare) -> Self {
+        Self {
+            beneficiary_id: value.beneficiary_id,
+            share: value.share.to_f32(),
+        }
+    }
+}
+
+impl From<StreakBonus> for StreakBonusView {
+    fn from(value: StreakBonus) -> Self {
+        Self {
+            threshold: value.threshold,
+            step: value.step.to_f32(),
+            max_days: value.max_days,
         }
     }
 }
@@ -40,6 +63,15 @@ impl From<WithdrawalFee> for WithdrawalFeeView {
         match value {
             WithdrawalFee::Fix(value) => WithdrawalFeeView::Fix(U128(value)),
             WithdrawalFee::Percent(value) => WithdrawalFeeView::Percent(value.to_f32()),
+            WithdrawalFee::Schedule(steps) => WithdrawalFeeView::Schedule(
+                steps
+                    .into_iter()
+                    .map(|step| FeeScheduleStepView {
+                        until: U64(step.until),
+                        fee: step.fee.to_f32(),
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -8,7 +8,7 @@ use sweat_jar_model::{Day, Local, Score, ScoreRecord, TimeHelper, Timezone, UTC}
 
 use crate::event::{emit, EventKind};
 
-const DAYS_STORED: usize = 2;
+pub(crate) const DAYS_STORED: usize = 2;
 
 type Chain = Vec<(Score, Local)>;
 
@@ -21,6 +21,10 @@ pub struct AccountScore {
     pub scores: [Score; DAYS_STORED],
     /// Score history values used for displaying it in application. Will not be invalidated during claim.
     pub scores_history: [Score; DAYS_STORED],
+    /// Number of consecutive finished days with the score reaching streak threshold.
+    /// Will not be invalidated during claim, only a day missing the threshold resets it.
+    #[serde(default)]
+    pub streak: u16,
 }
 
 impl AccountScore {
@@ -34,6 +38,7 @@ impl AccountScore {
             timezone,
             scores: [0; DAYS_STORED],
             scores_history: [0; DAYS_STORED],
+            streak: 0,
         }
     }
 
@@ -51,26 +56,34 @@ impl AccountScore {
         ScoreRecord {
             score,
             updated: self.updated,
+            streak: self.streak,
         }
     }
 
     pub fn active_score(&self) -> Score {
+        self.recent_scores().1
+    }
+
+    /// Returns scores walked today and yesterday in user's timezone.
+    pub fn recent_scores(&self) -> (Score, Score) {
         let update_day = self.update_day();
         let today = self.timezone.today();
 
         if update_day == today {
-            self.scores_history[1]
+            (self.scores_history[0], self.scores_history[1])
         } else if update_day == Local(today.0 - 1) {
-            self.scores_history[0]
+            (0, self.scores_history[0])
         } else {
-            0
+            (0, 0)
         }
     }
 
     /// On claim we need to clear active scores so they aren't claimed twice or more.
-    pub fn claim_score(&mut self) -> ScoreRecord {
+    /// Days which leave stored scores buffer on claim are finished, so they are counted for the streak.
+    pub fn claim_score(&mut self, streak_threshold: Option<Score>) -> ScoreRecord {
         let today = self.timezone.today();
         let update_day = self.update_day();
+        let streak = self.streak;
 
         let score = if today == update_day {
             let score = self.scores[1];
@@ -81,6 +94,8 @@ impl AccountScore {
             self.scores[0] = 0;
             self.scores[1] = 0;
 
+            self.update_streak(today, update_day, streak_threshold);
+
             // If scores were updated yesterday we shift history by 1 day
             // If older that yesterday then we wipe it
             if update_day == Local(today.0 - 1) {
@@ -97,7 +112,37 @@ impl AccountScore {
 
         self.updated = block_timestamp_ms().into();
 
-        ScoreRecord { score, updated }
+        ScoreRecord { score, updated, streak }
+    }
+
+    fn update_streak(&mut self, today: Day, update_day: Day, threshold: Option<Score>) {
+        let Some(threshold) = threshold else {
+            self.streak = 0;
+            return;
+        };
+
+        let days_passed = (today - update_day).0;
+
+        // The day before the last update leaves the buffer on any day change
+        let mut finished_days = vec![self.scores_history[1]];
+
+        // The day of the last update leaves the buffer if it was 2 or more days ago
+        if days_passed >= DAYS_STORED as u64 {
+            finished_days.push(self.scores_history[0]);
+        }
+
+        // There were days without any score between the last update and yesterday
+        if days_passed > DAYS_STORED as u64 {
+            finished_days.push(0);
+        }
+
+        for score in finished_days {
+            self.streak = if score >= threshold {
+                self.streak.saturating_add(1)
+            } else {
+                0
+            };
+        }
     }
 
     pub fn update(&mut self, chain: Chain) {
@@ -116,8 +161,8 @@ impl AccountScore {
     fn update_today(&mut self, chain: Chain) -> Vec<Score> {
         for (score, day) in chain {
             let day_index: usize = day.0.try_into().unwrap();
-            self.scores[day_index] = self.scores[day_index].checked_add(score).unwrap_or(u16::MAX);
-            self.scores_history[day_index] = self.scores_history[day_index].checked_add(score).unwrap_or(u16::MAX);
+            self.scores[day_index] = self.scores[day_index].saturating_add(score);
+            self.scores_history[day_index] = self.scores_history[day_index].saturating_add(score);
         }
         vec![]
     }
@@ -161,6 +206,7 @@ impl Default for AccountScore {
             timezone: Timezone::invalid(),
             scores: [0, 0],
             scores_history: [0, 0],
+            streak: 0,
         }
     }
 }
@@ -168,9 +214,10 @@ impl Default for AccountScore {
 #[cfg(test)]
 mod test {
     use near_sdk::env::block_timestamp_ms;
-    use sweat_jar_model::{Day, Timezone, MS_IN_DAY, MS_IN_HOUR, UTC};
+    use sweat_jar_model::{Day, Score, Timezone, MS_IN_DAY, MS_IN_HOUR, UTC};
 
     use crate::{
+        common::tests::Context,
         product::model::Product,
         score::{account_score::Chain, AccountScore},
         test_builder::TestBuilder,
@@ -225,7 +272,7 @@ mod test {
             0.05
         );
 
-        assert_eq!(account_score.claim_score().score, vec![2000, 3000]);
+        assert_eq!(account_score.claim_score(None).score, vec![2000, 3000]);
 
         assert_eq!(
             product.apy_for_score(&account_score.claimable_score().score).to_f32(),
@@ -250,6 +297,7 @@ mod test {
             timezone: Timezone::hour_shift(0),
             scores: [1000, 2000],
             scores_history: [1000, 2000],
+            streak: 0,
         };
 
         let mut ctx = TestBuilder::new().build();
@@ -260,18 +308,74 @@ mod test {
 
         assert_eq!(score.updated, (MS_IN_DAY * 10).into());
         assert_eq!(score.scores(), (1006, 2005));
-        assert_eq!(score.claim_score().score, vec![2005]);
+        assert_eq!(score.claim_score(None).score, vec![2005]);
         assert_eq!(score.active_score(), 2005);
 
         ctx.set_block_timestamp_in_ms(MS_IN_DAY * 11);
-        assert_eq!(score.claim_score().score, vec![1006, 0]);
+        assert_eq!(score.claim_score(None).score, vec![1006, 0]);
         assert_eq!(score.active_score(), 1006);
 
         ctx.set_block_timestamp_in_ms(MS_IN_DAY * 12);
-        assert_eq!(score.claim_score().score, vec![0, 0]);
+        assert_eq!(score.claim_score(None).score, vec![0, 0]);
         assert_eq!(score.active_score(), 0);
     }
 
+    #[test]
+    fn score_update_saturates() {
+        let mut score = AccountScore {
+            updated: UTC(MS_IN_DAY * 10),
+            timezone: Timezone::hour_shift(0),
+            scores: [u32::MAX - 10, 0],
+            scores_history: [u32::MAX - 10, 0],
+            streak: 0,
+        };
+
+        let mut ctx = TestBuilder::new().build();
+
+        ctx.set_block_timestamp_in_ms(MS_IN_DAY * 10);
+
+        score.update(vec![
+            (100_000, (MS_IN_DAY * 10).into()),
+            (70_000, (MS_IN_DAY * 9).into()),
+        ]);
+
+        assert_eq!(score.scores(), (u32::MAX, 70_000));
+        assert_eq!(score.scores_history, [u32::MAX, 70_000]);
+    }
+
+    #[test]
+    fn streak() {
+        const THRESHOLD: Option<Score> = Some(5_000);
+
+        let mut ctx = TestBuilder::new().build();
+
+        ctx.set_block_timestamp_in_ms(MS_IN_DAY * 10);
+
+        let mut score = AccountScore::new(Timezone::hour_shift(0));
+
+        let mut walk = |ctx: &mut Context, day: u64, steps: Score| {
+            ctx.set_block_timestamp_in_ms(MS_IN_DAY * day);
+            let streak = score.claim_score(THRESHOLD).streak;
+            if steps > 0 {
+                score.update(vec![(steps, (MS_IN_DAY * day).into())]);
+            }
+            (streak, score.streak)
+        };
+
+        assert_eq!(walk(&mut ctx, 10, 6_000), (0, 0));
+        assert_eq!(walk(&mut ctx, 11, 7_000), (0, 0));
+        assert_eq!(walk(&mut ctx, 12, 8_000), (0, 1));
+        assert_eq!(walk(&mut ctx, 13, 9_000), (1, 2));
+        // Nothing on day 14, but both days 12 and 13 are finished
+        assert_eq!(walk(&mut ctx, 15, 0), (2, 4));
+        // Day 14 is missed
+        assert_eq!(walk(&mut ctx, 16, 9_000), (4, 0));
+        assert_eq!(walk(&mut ctx, 17, 9_000), (0, 0));
+        assert_eq!(walk(&mut ctx, 18, 0), (0, 1));
+        // Days 17 and 18 are finished and there were no updates for 19 and 20
+        assert_eq!(walk(&mut ctx, 22, 0), (1, 0));
+    }
+
     #[test]
     fn active_score() {
         let score = AccountScore {
@@ -279,6 +383,7 @@ mod test {
             timezone: Timezone::hour_shift(0),
             scores: [1000, 2000],
             scores_history: [1000, 2000],
+            streak: 0,
         };
 
         let mut ctx = TestBuilder::new().build();
@@ -286,13 +391,16 @@ mod test {
         ctx.set_block_timestamp_in_ms(MS_IN_DAY * 10);
 
         assert_eq!(score.active_score(), 2000);
+        assert_eq!(score.recent_scores(), (1000, 2000));
 
         ctx.set_block_timestamp_in_ms(MS_IN_DAY * 11);
 
         assert_eq!(score.active_score(), 1000);
+        assert_eq!(score.recent_scores(), (0, 1000));
 
         ctx.set_block_timestamp_in_ms(MS_IN_DAY * 12);
 
         assert_eq!(score.active_score(), 0);
+        assert_eq!(score.recent_scores(), (0, 0));
     }
 }
@@ -0,0 +1,47 @@
+use near_sdk::near;
+use sweat_jar_model::{Score, Timezone, UTC};
+
+use crate::score::{account_score::DAYS_STORED, AccountScore};
+
+/// Account score layout from the time when `Score` was stored as `u16`.
+#[near]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AccountScoreV1 {
+    pub updated: UTC,
+    pub timezone: Timezone,
+    pub scores: [u16; DAYS_STORED],
+    pub scores_history: [u16; DAYS_STORED],
+}
+
+/// Account score layout before streaks were introduced.
+#[near]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AccountScoreV2 {
+    pub updated: UTC,
+    pub timezone: Timezone,
+    pub scores: [Score; DAYS_STORED],
+    pub scores_history: [Score; DAYS_STORED],
+}
+
+impl From<AccountScoreV1> for AccountScoreV2 {
+    fn from(value: AccountScoreV1) -> Self {
+        Self {
+            updated: value.updated,
+            timezone: value.timezone,
+            scores: value.scores.map(Into::into),
+            scores_history: value.scores_history.map(Into::into),
+        }
+    }
+}
+
+impl From<AccountScoreV2> for AccountScore {
+    fn from(value: AccountScoreV2) -> Self {
+        Self {
+            updated: value.updated,
+            timezone: value.timezone,
+            scores: value.scores,
+            scores_history: value.scores_history,
+            streak: 0,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 mod account_score;
 mod charts;
+mod legacy;
 mod score_api;
 mod tests;
 
 pub use account_score::*;
+pub use legacy::*;
@@ -1,15 +1,14 @@
 use near_sdk::{
-    env,
     env::block_timestamp_ms,
     json_types::{I64, U128},
-    near_bindgen, AccountId,
+    near_bindgen, AccountId, Timestamp,
 };
-use sweat_jar_model::{api::ScoreApi, Score, U32, UTC};
+use sweat_jar_model::{api::ScoreApi, Score, ScoreProjectionView, U32, UTC};
 
 use crate::{
     event::{emit, EventKind, ScoreData},
-    jar::model::JarCache,
-    Contract, ContractExt,
+    jar::model::{Jar, JarCache},
+    Contract, ContractExt, ProductsStorage,
 };
 
 #[near_bindgen]
@@ -24,34 +23,16 @@ impl ScoreApi for Contract {
         for (account, new_score) in batch {
             self.migrate_account_if_needed(&account);
 
-            let account_jars = self.accounts.entry(account.clone()).or_default();
-
             assert!(
-                account_jars.has_score_jars(),
+                self.accounts
+                    .get(&account)
+                    .is_some_and(|account_jars| account_jars.has_score_jars()),
                 "Account '{account}' doesn't have score jars"
             );
 
-            let score = account_jars.score.claim_score();
-
-            for jar in &mut account_jars.jars {
-                let product = self
-                    .products
-                    .get(&jar.product_id)
-                    .unwrap_or_else(|| env::panic_str(&format!("Product '{}' doesn't exist", jar.product_id)));
-
-                if !product.is_score_product() {
-                    continue;
-                }
+            self.settle_score_interest(&account, now);
 
-                let (interest, remainder) = jar.get_interest(&score, &product, now);
-
-                jar.claim_remainder = remainder;
-
-                jar.cache = Some(JarCache {
-                    updated_at: now,
-                    interest,
-                });
-            }
+            let account_jars = self.accounts.get_mut(&account).expect("Account is checked above");
 
             // Convert walkchain to user timezone
             let converted_score = new_score
@@ -65,8 +46,9 @@ impl ScoreApi for Contract {
                 account_id: account,
                 score: new_score
                     .into_iter()
-                    .map(|(score, timestamp)| (U32(score.into()), timestamp))
+                    .map(|(score, timestamp)| (U32(score), timestamp))
                     .collect(),
+                streak: account_jars.score.streak,
             });
         }
 
@@ -84,4 +66,102 @@ impl ScoreApi for Contract {
 
         Some(u128::from(account.active_score()).into())
     }
+
+    fn get_streak(&self, account_id: AccountId) -> Option<u16> {
+        self.get_score(&account_id).map(|score| score.streak)
+    }
+
+    fn get_score_projection(&self, account_id: AccountId) -> Vec<ScoreProjectionView> {
+        let Some(account) = self.accounts.get(&account_id) else {
+            return vec![];
+        };
+
+        let Some(score) = account.score() else {
+            return vec![];
+        };
+
+        let now = block_timestamp_ms();
+        let claimable_score = score.claimable_score();
+        let (today, yesterday) = score.recent_scores();
+
+        account
+            .jars
+            .iter()
+            .filter_map(|jar| {
+                let product = self.get_product(&jar.product_id);
+
+                if !product.is_score_product() {
+                    return None;
+                }
+
+                let cached_interest = jar.cache.map(|cache| cache.interest).unwrap_or_default();
+                let (interest, _) = jar.get_interest(&claimable_score, product, now);
+
+                let streak = claimable_score.streak;
+                let apy = |score: Score| {
+                    if jar.is_penalty_applied {
+                        return 0.0;
+                    }
+
+                    product
+                        .apy_with_streak(product.apy_for_score(&[score]), streak)
+                        .to_f32()
+                };
+
+                Some(ScoreProjectionView {
+                    jar_id: jar.id.into(),
+                    apy_today: apy(today),
+                    apy_yesterday: apy(yesterday),
+                    max_apy: apy(product.score_cap),
+                    pending_interest: (interest - cached_interest).into(),
+                    max_interest: jar.get_max_score_interest(product, streak, now).into(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Contract {
+    /// Claims account score and caches the interest it brings for all score based jars of the account.
+    /// Must be called before any change of the score or jar principal.
+    pub(crate) fn settle_score_interest(&mut self, account: &AccountId, now: Timestamp) {
+        let streak_threshold = self
+            .accounts
+            .get(account)
+            .and_then(|account_jars| self.streak_threshold(&account_jars.jars));
+
+        let Some(account_jars) = self.accounts.get_mut(account) else {
+            return;
+        };
+
+        let score = account_jars.score.claim_score(streak_threshold);
+
+        for jar in &mut account_jars.jars {
+            let product = self.products.get_product(&jar.product_id);
+
+            if !product.is_score_product() {
+                continue;
+            }
+
+            let (interest, remainder) = jar.get_interest(&score, product, now);
+
+            jar.claim_remainder = remainder;
+
+            jar.cache = Some(JarCache {
+                updated_at: now,
+                interest,
+            });
+        }
+    }
+
+    /// Daily score required to continue a streak for the account.
+    /// All products with streak bonus share the same threshold, it is checked on product registration.
+    pub(crate) fn streak_threshold(&self, jars: &[Jar]) -> Option<Score> {
+        jars.iter().find_map(|jar| {
+            self.get_product(&jar.product_id)
+                .streak
+                .as_ref()
+                .map(|streak| streak.threshold)
+        })
+    }
 }
@@ -6,14 +6,17 @@ use near_sdk::{
     json_types::{I64, U128},
     serde_json::json,
     store::LookupMap,
-    test_utils::test_env::{alice, bob},
+    test_utils::{
+        get_logs,
+        test_env::{alice, bob},
+    },
     NearToken, Timestamp,
 };
 use sweat_jar_model::{
-    api::{JarApi, ProductApi, ScoreApi, WithdrawApi},
+    api::{JarApi, PenaltyApi, ProductApi, ScoreApi, WithdrawApi},
     jar::JarId,
-    product::RegisterProductCommand,
-    Score, Timezone, MS_IN_DAY, MS_IN_HOUR, UTC,
+    product::{RegisterProductCommand, StreakBonusDto},
+    Score, Timezone, UDecimal, MS_IN_DAY, MS_IN_HOUR, U32, UTC,
 };
 
 use crate::{
@@ -22,6 +25,7 @@ use crate::{
         tests::Context,
     },
     jar::model::AccountJarsLegacy,
+    product::model::StreakBonus,
     test_builder::{JarField, ProductField::*, TestAccess, TestBuilder},
     test_utils::{admin, expect_panic, UnwrapPromise, PRODUCT, SCORE_PRODUCT},
     StorageKey,
@@ -49,6 +53,9 @@ fn create_invalid_step_product() {
         public_key: None,
         is_enabled: false,
         score_cap: 1000,
+        streak: None,
+        unbonding_period: None,
+        fee_split: None,
     };
 
     ctx.switch_account(admin());
@@ -66,6 +73,73 @@ fn create_invalid_step_product() {
     });
 }
 
+#[test]
+fn create_step_product_with_score_cap_above_u16() {
+    let mut ctx = TestBuilder::new().build();
+
+    let command = RegisterProductCommand {
+        id: "aa".to_string(),
+        apy_default: (0.into(), 0),
+        apy_fallback: None,
+        cap_min: Default::default(),
+        cap_max: Default::default(),
+        terms: Default::default(),
+        withdrawal_fee: None,
+        public_key: None,
+        is_enabled: true,
+        score_cap: 70_000,
+        streak: None,
+        unbonding_period: None,
+        fee_split: None,
+    };
+
+    ctx.switch_account(admin());
+    ctx.set_deposit_yocto(1);
+
+    ctx.contract().register_product(command);
+
+    assert_eq!(ctx.contract().get_product(&"aa".to_string()).score_cap, 70_000);
+}
+
+#[test]
+fn create_streak_products_with_different_thresholds() {
+    let mut ctx = TestBuilder::new().build();
+
+    let streak_command = |id: &str, threshold: Score| RegisterProductCommand {
+        id: id.to_string(),
+        apy_default: (0.into(), 0),
+        apy_fallback: None,
+        cap_min: Default::default(),
+        cap_max: Default::default(),
+        terms: Default::default(),
+        withdrawal_fee: None,
+        public_key: None,
+        is_enabled: true,
+        score_cap: 20_000,
+        streak: Some(StreakBonusDto {
+            threshold,
+            step: (10.into(), 2),
+            max_days: 3,
+        }),
+        unbonding_period: None,
+        fee_split: None,
+    };
+
+    ctx.switch_account(admin());
+    ctx.set_deposit_yocto(1);
+
+    ctx.contract().register_product(streak_command("5k", 5_000));
+    ctx.contract().register_product(streak_command("5k_other", 5_000));
+
+    expect_panic(
+        &ctx,
+        "All products with streak bonus must have the same threshold",
+        || {
+            ctx.contract().register_product(streak_command("10k", 10_000));
+        },
+    );
+}
+
 /// 12% jar should have the same interest as 12_000 score jar walking to the limit every day
 /// Also this method tests score cap
 #[test]
@@ -339,7 +413,7 @@ fn timestamps() {
         .jars
         .first_mut()
         .unwrap()
-        .created_at = 1729692817027; // Wed Oct 23 2024 14:13:37
+        .set_created_at(1729692817027); // Wed Oct 23 2024 14:13:37
 
     ctx.set_block_timestamp_in_ms(1729694971000); // Wed Oct 23 2024 14:49:31
 
@@ -379,7 +453,7 @@ fn test_steps_history() {
         .jars
         .first_mut()
         .unwrap()
-        .created_at = BASE_TIME;
+        .set_created_at(BASE_TIME);
 
     let check_score_interest = |ctx: &Context, val: u128| {
         assert_eq!(ctx.contract().get_score_interest(alice()), Some(U128(val)));
@@ -435,7 +509,7 @@ fn record_max_score() {
 
     ctx.set_block_timestamp_in_days(1);
 
-    assert_eq!(ctx.contract().get_score_interest(alice()).unwrap().0, 65535);
+    assert_eq!(ctx.contract().get_score_interest(alice()).unwrap().0, 100_000);
 }
 
 #[test]
@@ -455,7 +529,7 @@ fn claim_when_there_were_no_walkchains_for_some_time() {
     account.score.updated = 1732653318018.into(); // Tue Nov 26 2024 20:35:18
     account.score.scores = [15100, 0];
 
-    account.jars.first_mut().unwrap().created_at = 1733139450015; // Mon Dec 02 2024 11:37:30
+    account.jars.first_mut().unwrap().set_created_at(1733139450015); // Mon Dec 02 2024 11:37:30
 
     drop(binding);
 
@@ -472,6 +546,7 @@ fn test_steps_and_migration() {
         .product(SCORE_PRODUCT, [APY(0), TermDays(10), ScoreCap(20_000)])
         .build();
 
+    ctx.contract().legacy_accounts.is_complete = false;
     ctx.contract().account_jars_v1.insert(
         alice(),
         AccountJarsLegacy {
@@ -500,3 +575,234 @@ fn test_steps_and_migration() {
 
     ctx.record_score(UTC(0), 25000, alice());
 }
+
+#[test]
+fn score_projection() {
+    const ALICE_JAR: JarId = 0;
+
+    set_test_log_events(false);
+
+    let mut ctx = TestBuilder::new()
+        .product(PRODUCT, APY(12))
+        .jar(1, ())
+        .product(SCORE_PRODUCT, [APY(0), ScoreCap(20_000)])
+        .jar(ALICE_JAR, JarField::Timezone(Timezone::hour_shift(0)))
+        .build();
+
+    assert!(ctx.contract().get_score_projection(bob()).is_empty());
+
+    ctx.set_block_timestamp_in_days(1);
+
+    ctx.record_score(UTC(MS_IN_DAY), 5_000, alice());
+    ctx.record_score(UTC(0), 30_000, alice());
+
+    let projection = ctx.contract().get_score_projection(alice());
+    assert_eq!(projection.len(), 1);
+
+    let projection = projection.first().unwrap().clone();
+    assert_eq!(projection.jar_id, ALICE_JAR.into());
+    assert_eq!(projection.apy_today, 0.05);
+    assert_eq!(projection.apy_yesterday, 0.2);
+    assert_eq!(projection.max_apy, 0.2);
+    assert_eq!(projection.pending_interest.0, ctx.interest(ALICE_JAR));
+    assert_eq!(projection.max_interest, projection.pending_interest);
+
+    ctx.set_block_timestamp_in_days(2);
+
+    let projection = ctx.contract().get_score_projection(alice()).first().unwrap().clone();
+    assert_eq!(projection.apy_today, 0.0);
+    assert_eq!(projection.apy_yesterday, 0.05);
+    assert_eq!(projection.pending_interest.0, ctx.interest(ALICE_JAR));
+
+    ctx.record_score(UTC(MS_IN_DAY * 2), 1_000, alice());
+
+    assert_eq!(
+        ctx.jar(ALICE_JAR).cache.unwrap().interest,
+        projection.pending_interest.0
+    );
+    assert_eq!(
+        ctx.contract()
+            .get_score_projection(alice())
+            .first()
+            .unwrap()
+            .pending_interest
+            .0,
+        0
+    );
+}
+
+#[test]
+fn streak_bonus() {
+    const ALICE_JAR: JarId = 0;
+
+    set_test_log_events(false);
+
+    let mut ctx = TestBuilder::new()
+        .product(
+            SCORE_PRODUCT,
+            [
+                APY(0),
+                ScoreCap(20_000),
+                Streak(StreakBonus {
+                    threshold: 5_000,
+                    step: UDecimal::new(10, 2),
+                    max_days: 3,
+                }),
+            ],
+        )
+        .jar(ALICE_JAR, JarField::Timezone(Timezone::hour_shift(0)))
+        .build();
+
+    let mut credited = vec![];
+    let mut previous_interest = 0;
+
+    for day in 0..7 {
+        ctx.set_block_timestamp_in_days(day);
+
+        ctx.record_score(UTC(day * MS_IN_DAY), 10_000, alice());
+
+        let interest = ctx.interest(ALICE_JAR);
+        credited.push(interest - previous_interest);
+        previous_interest = interest;
+
+        assert_eq!(ctx.contract().get_streak(alice()), Some(day.saturating_sub(1) as u16));
+    }
+
+    let projection = ctx.contract().get_score_projection(alice()).first().unwrap().clone();
+    assert_eq!(projection.apy_today, 0.13);
+    assert_eq!(projection.max_apy, 0.26);
+
+    // Score of a day is credited on the next day with the streak of days finished before it
+    let base = credited[2];
+    let with_bonus = |percent: u128| base * percent / 100;
+
+    for (day, percent) in [(3, 110), (4, 120), (5, 130), (6, 130)] {
+        let diff = credited[day] as i128 - with_bonus(percent) as i128;
+        assert!(diff.abs() <= 2, "Day {day}. Diff is too big {diff}");
+    }
+
+    // Missing a day resets the streak
+    ctx.set_block_timestamp_in_days(8);
+    ctx.record_score(UTC(8 * MS_IN_DAY), 10_000, alice());
+
+    assert_eq!(ctx.contract().get_streak(alice()), Some(0));
+}
+
+#[test]
+fn top_up_step_jar() {
+    const ALICE_JAR: JarId = 0;
+
+    set_test_log_events(false);
+
+    let mut ctx = TestBuilder::new()
+        .product(
+            SCORE_PRODUCT,
+            [APY(0), ScoreCap(20_000), TopUp(true), MaxCap(u128::MAX)],
+        )
+        .jar(ALICE_JAR, JarField::Timezone(Timezone::hour_shift(0)))
+        .build();
+
+    let principal = ctx.jar(ALICE_JAR).principal;
+
+    ctx.set_block_timestamp_in_days(0);
+    ctx.record_score(UTC(0), 10_000, alice());
+
+    ctx.set_block_timestamp_in_days(1);
+    ctx.record_score(UTC(MS_IN_DAY), 10_000, alice());
+
+    let interest_for_day = ctx.interest(ALICE_JAR);
+    assert_ne!(interest_for_day, 0);
+
+    // Score walked today will be credited with increased principal
+    ctx.contract().top_up(&alice(), ALICE_JAR, U128(principal));
+
+    assert_eq!(ctx.jar(ALICE_JAR).principal, principal * 2);
+    assert_eq!(ctx.interest(ALICE_JAR), interest_for_day);
+
+    ctx.set_block_timestamp_in_days(2);
+
+    // Pending score interest is settled with the principal it was accrued with
+    let interest_before_top_up = ctx.interest(ALICE_JAR);
+    ctx.contract().top_up(&alice(), ALICE_JAR, U128(principal));
+
+    assert_eq!(ctx.interest(ALICE_JAR), interest_before_top_up);
+
+    let diff = (interest_before_top_up - interest_for_day) as i128 - (interest_for_day * 2) as i128;
+    assert!(diff.abs() <= 1, "Diff is too big {diff}");
+
+    ctx.set_block_timestamp_in_days(3);
+    ctx.record_score(UTC(3 * MS_IN_DAY), 10_000, alice());
+
+    // Score walked before the top up is already claimed
+    assert_eq!(ctx.interest(ALICE_JAR), interest_before_top_up);
+}
+
+#[test]
+fn score_penalty() {
+    const ALICE_JAR: JarId = 0;
+
+    set_test_log_events(false);
+
+    let mut ctx = TestBuilder::new()
+        .product(SCORE_PRODUCT, [APY(0), ScoreCap(20_000)])
+        .jar(ALICE_JAR, JarField::Timezone(Timezone::hour_shift(0)))
+        .build();
+
+    ctx.set_block_timestamp_in_days(0);
+    ctx.record_score(UTC(0), 10_000, alice());
+
+    // Score recorded before the penalty is settled with the regular multiplier
+    ctx.set_block_timestamp_in_days(1);
+    ctx.switch_account(admin());
+    ctx.contract().set_penalty(alice(), U32(ALICE_JAR), true);
+
+    let interest_for_day = ctx.interest(ALICE_JAR);
+    assert_ne!(interest_for_day, 0);
+
+    ctx.record_score(UTC(MS_IN_DAY), 10_000, alice());
+
+    ctx.set_block_timestamp_in_days(2);
+    ctx.record_score(UTC(2 * MS_IN_DAY), 10_000, alice());
+
+    assert_eq!(ctx.interest(ALICE_JAR), interest_for_day);
+
+    let projection = ctx.contract().get_score_projection(alice()).first().unwrap().clone();
+    assert_eq!(projection.apy_today, 0.0);
+    assert_eq!(projection.pending_interest.0, 0);
+    assert_eq!(projection.max_interest.0, 0);
+
+    ctx.switch_account(admin());
+    ctx.contract().set_penalty(alice(), U32(ALICE_JAR), false);
+
+    assert!(!ctx.jar(ALICE_JAR).is_penalty_applied);
+    assert_eq!(ctx.interest(ALICE_JAR), interest_for_day);
+
+    // Score claimed after the penalty is canceled brings interest again
+    ctx.set_block_timestamp_in_days(3);
+    ctx.record_score(UTC(3 * MS_IN_DAY), 10_000, alice());
+
+    let diff = ctx.interest(ALICE_JAR) as i128 - (interest_for_day * 2) as i128;
+    assert!(diff.abs() <= 1, "Diff is too big {diff}");
+}
+
+#[test]
+fn batch_score_penalty_emits_both_events() {
+    const ALICE_JAR: JarId = 0;
+
+    let mut ctx = TestBuilder::new()
+        .product(SCORE_PRODUCT, [APY(0), ScoreCap(20_000)])
+        .jar(ALICE_JAR, JarField::Timezone(Timezone::hour_shift(0)))
+        .build();
+
+    ctx.switch_account(admin());
+    ctx.contract()
+        .batch_set_penalty(vec![(alice(), vec![U32(ALICE_JAR)])], true);
+
+    let logs = get_logs();
+
+    assert!(logs
+        .iter()
+        .any(|log| log.contains(r#""event": "batch_apply_score_penalty""#)));
+    // Indexers rely on `batch_apply_penalty` being emitted for every batch, even without constant APY jars
+    assert!(logs.iter().any(|log| log.contains(r#""event": "batch_apply_penalty""#)));
+}
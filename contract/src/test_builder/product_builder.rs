@@ -1,6 +1,6 @@
-use sweat_jar_model::{Score, MS_IN_DAY, MS_IN_MINUTE};
+use sweat_jar_model::{Score, TokenAmount, MS_IN_DAY, MS_IN_MINUTE};
 
-use crate::product::model::Product;
+use crate::product::model::{Product, StreakBonus};
 
 pub(crate) trait ProductBuilder: Sized {
     fn apply(self, product: Product) -> Product;
@@ -16,6 +16,9 @@ pub(crate) enum ProductField {
     TermDays(u64),
     #[allow(dead_code)]
     TermMinutes(u64),
+    Streak(StreakBonus),
+    TopUp(bool),
+    MaxCap(TokenAmount),
 }
 
 impl ProductBuilder for ProductField {
@@ -25,6 +28,12 @@ impl ProductBuilder for ProductField {
             ProductField::ScoreCap(cap) => product.score_cap(cap),
             ProductField::TermDays(days) => product.lockup_term(days * MS_IN_DAY),
             ProductField::TermMinutes(days) => product.lockup_term(days * MS_IN_MINUTE),
+            ProductField::Streak(streak) => product.streak(streak),
+            ProductField::TopUp(allows_top_up) => product.with_allows_top_up(allows_top_up),
+            ProductField::MaxCap(max) => {
+                let min = product.cap.min;
+                product.cap(min, max)
+            }
         }
     }
 }
@@ -25,7 +25,7 @@ pub trait TestAccess {
 
 impl TestAccess for Context {
     fn _product(&self, id: &str) -> Product {
-        self.contract().get_product(&id.to_string())
+        self.contract().get_product(&id.to_string()).clone()
     }
 
     fn interest(&self, id: JarId) -> u128 {
@@ -37,6 +37,11 @@ impl Jar {
             is_pending_withdraw: false,
             is_penalty_applied: false,
             claim_remainder: Default::default(),
+            unbonding: None,
+            auto_renew: false,
+            lock: None,
+            total_deposited: 1_000_000,
+            term_started_at: 0,
         }
         .into()
     }
@@ -53,14 +58,20 @@ impl Jar {
 
     pub(crate) fn principal(mut self, principal: TokenAmount) -> Jar {
         self.principal = principal;
+        self.total_deposited = principal;
         self
     }
 
     pub(crate) fn created_at(mut self, created_at: Timestamp) -> Jar {
-        self.created_at = created_at;
+        self.set_created_at(created_at);
         self
     }
 
+    pub(crate) fn set_created_at(&mut self, created_at: Timestamp) {
+        self.created_at = created_at;
+        self.term_started_at = created_at;
+    }
+
     pub(crate) fn pending_withdraw(mut self) -> Jar {
         self.is_pending_withdraw = true;
         self
@@ -0,0 +1,245 @@
+use near_sdk::{env, env::panic_str, ext_contract, json_types::U128, near_bindgen, require, AccountId, PromiseOrValue};
+use sweat_jar_model::{
+    api::UnbondApi,
+    jar::{JarId, JarIdView, LockOperation, UnbondingView},
+    withdraw::{Fee, WithdrawView},
+    U32,
+};
+
+#[cfg(not(test))]
+use crate::ft_interface::FungibleTokenInterface;
+use crate::{
+    assert::{assert_not_locked, assert_sufficient_balance},
+    event::{emit, emit_fee_split, EventKind, UnbondData},
+    internal::is_promise_success,
+    jar::model::Unbonding,
+    score::AccountScore,
+    Contract, ContractExt,
+};
+
+#[allow(dead_code)] // False positive since rust 1.78. It is used from `ext_contract` macro.
+#[ext_contract(ext_self)]
+pub trait UnbondCallbacks {
+    fn after_finalize_unbond(
+        &mut self,
+        account_id: AccountId,
+        jar_id: JarId,
+        unbonding: Unbonding,
+        fee: Option<Fee>,
+    ) -> WithdrawView;
+}
+
+impl Contract {
+    /// Moves `amount` of jar's principal to unbonding instead of transferring it.
+    /// Is called from `withdraw` for products with unbonding period.
+    pub(crate) fn request_unbond(
+        &mut self,
+        account_id: &AccountId,
+        jar_id: JarId,
+        amount: Option<U128>,
+    ) -> WithdrawView {
+        let jar = self.get_jar_internal(account_id, jar_id);
+
+        assert_not_locked(&jar);
+        require!(
+            jar.unbonding.is_none(),
+            "Jar already has pending unbond. Finalize or cancel it first"
+        );
+
+        let amount = amount.map_or(jar.principal, |value| value.0);
+
+        require!(amount > 0, "Nothing to unbond");
+        assert_sufficient_balance(&jar, amount);
+
+        let product = self.get_product(&jar.product_id).clone();
+        let now = env::block_timestamp_ms();
+
+        if product.is_score_product() {
+            self.settle_score_interest(account_id, now);
+        }
+
+        let unbonding = self
+            .get_jar_mut_internal(account_id, jar_id)
+            .unbond(amount, &product, now)
+            .unbonding
+            .expect("Unbonding is set above");
+
+        emit(EventKind::RequestUnbond(UnbondData {
+            id: jar_id,
+            amount: unbonding.amount.into(),
+            unlock_at: unbonding.unlock_at,
+        }));
+
+        WithdrawView::new(0, None)
+    }
+
+    /// The jar is closed by the same rule as after a regular withdrawal.
+    pub(crate) fn after_finalize_unbond_internal(
+        &mut self,
+        account_id: AccountId,
+        jar_id: JarId,
+        unbonding: Unbonding,
+        fee: Option<Fee>,
+        is_promise_success: bool,
+    ) -> WithdrawView {
+        let jar = self.get_jar_mut_internal(&account_id, jar_id);
+        jar.unlock();
+
+        if !is_promise_success {
+            jar.unbonding = Some(unbonding);
+
+            return WithdrawView::new(0, None);
+        }
+
+        let jar = self.get_jar_internal(&account_id, jar_id);
+        let product = self.get_product(&jar.product_id);
+        let score = self
+            .get_score(&account_id)
+            .map(AccountScore::claimable_score)
+            .unwrap_or_default();
+
+        if jar.should_be_closed(&score, product, env::block_timestamp_ms()) {
+            self.delete_jar(&account_id, jar_id);
+        }
+
+        let withdrawal_result = WithdrawView::new(unbonding.amount, fee);
+
+        emit(EventKind::FinalizeUnbond((
+            jar_id,
+            withdrawal_result.fee,
+            withdrawal_result.withdrawn_amount,
+        )));
+        emit_fee_split(vec![(jar_id, &withdrawal_result)]);
+
+        withdrawal_result
+    }
+}
+
+#[near_bindgen]
+impl UnbondApi for Contract {
+    fn get_pending_unbonds(&self, account_id: AccountId) -> Vec<UnbondingView> {
+        self.account_jars(&account_id)
+            .iter()
+            .filter_map(|jar| {
+                jar.unbonding.map(|unbonding| UnbondingView {
+                    jar_id: U32(jar.id),
+                    amount: unbonding.amount.into(),
+                    unlock_at: unbonding.unlock_at.into(),
+                })
+            })
+            .collect()
+    }
+
+    fn finalize_unbond(&mut self, jar_id: JarIdView) -> PromiseOrValue<WithdrawView> {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let jar_id = jar_id.0;
+        let jar = self.get_jar_internal(&account_id, jar_id);
+
+        assert_not_locked(&jar);
+
+        let unbonding = jar
+            .unbonding
+            .unwrap_or_else(|| panic_str("Jar doesn't have pending unbond"));
+
+        let now = env::block_timestamp_ms();
+
+        require!(now >= unbonding.unlock_at, "Unbonding period is not over yet");
+
+        let product = self.get_product(&jar.product_id);
+        let fee = self.make_fee(product, Self::get_fee_for_amount(product, &jar, unbonding.amount));
+
+        let jar = self.get_jar_mut_internal(&account_id, jar_id);
+        jar.unbonding = None;
+        jar.lock(LockOperation::FinalizeUnbond, now);
+
+        self.transfer_unbonded(&account_id, jar_id, unbonding, fee)
+    }
+
+    fn cancel_unbond(&mut self, jar_id: JarIdView) {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let jar_id = jar_id.0;
+        let jar = self.get_jar_internal(&account_id, jar_id);
+
+        assert_not_locked(&jar);
+
+        let product = self.get_product(&jar.product_id).clone();
+        let now = env::block_timestamp_ms();
+
+        if product.is_score_product() {
+            self.settle_score_interest(&account_id, now);
+        }
+
+        let unbonding = self
+            .get_jar_mut_internal(&account_id, jar_id)
+            .cancel_unbond(&product, now);
+
+        emit(EventKind::CancelUnbond(UnbondData {
+            id: jar_id,
+            amount: unbonding.amount.into(),
+            unlock_at: unbonding.unlock_at,
+        }));
+    }
+}
+
+#[cfg(not(test))]
+#[mutants::skip] // Covered by integration tests
+impl Contract {
+    fn transfer_unbonded(
+        &mut self,
+        account_id: &AccountId,
+        jar_id: JarId,
+        unbonding: Unbonding,
+        fee: Option<Fee>,
+    ) -> PromiseOrValue<WithdrawView> {
+        self.ft_contract()
+            .ft_transfer(account_id, unbonding.amount, "finalize_unbond", &fee)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(crate::common::gas_data::GAS_FOR_AFTER_WITHDRAW)
+                    .after_finalize_unbond(account_id.clone(), jar_id, unbonding, fee),
+            )
+            .into()
+    }
+}
+
+#[cfg(test)]
+impl Contract {
+    fn transfer_unbonded(
+        &mut self,
+        account_id: &AccountId,
+        jar_id: JarId,
+        unbonding: Unbonding,
+        fee: Option<Fee>,
+    ) -> PromiseOrValue<WithdrawView> {
+        let withdrawn = self.after_finalize_unbond_internal(
+            account_id.clone(),
+            jar_id,
+            unbonding,
+            fee,
+            crate::common::test_data::get_test_future_success(),
+        );
+
+        PromiseOrValue::Value(withdrawn)
+    }
+}
+
+#[near_bindgen]
+#[mutants::skip] // Covered by integration tests
+impl UnbondCallbacks for Contract {
+    #[private]
+    fn after_finalize_unbond(
+        &mut self,
+        account_id: AccountId,
+        jar_id: JarId,
+        unbonding: Unbonding,
+        fee: Option<Fee>,
+    ) -> WithdrawView {
+        self.after_finalize_unbond_internal(account_id, jar_id, unbonding, fee, is_promise_success())
+    }
+}
@@ -0,0 +1,198 @@
+#![cfg(test)]
+
+use near_sdk::{
+    json_types::{U128, U64},
+    test_utils::test_env::{alice, bob},
+    AccountId,
+};
+use sweat_jar_model::{
+    api::{JarApi, UnbondApi, WithdrawApi},
+    jar::UnbondingView,
+    withdraw::WithdrawView,
+    MS_IN_DAY, MS_IN_YEAR, U32,
+};
+
+use crate::{
+    common::{test_data::set_test_future_success, tests::Context},
+    jar::model::Jar,
+    product::model::{Product, WithdrawalFee},
+    test_utils::{admin, expect_panic, UnwrapPromise, PRINCIPAL},
+};
+
+const UNBONDING_PERIOD: u64 = MS_IN_DAY * 7;
+
+fn prepare_jar(product: Product) -> (AccountId, Context) {
+    let alice = alice();
+
+    let mut context = Context::new(admin())
+        .with_products(&[product.flexible().unbonding_period(UNBONDING_PERIOD)])
+        .with_jars(&[Jar::new(0)]);
+
+    context.switch_account(&alice);
+
+    (alice, context)
+}
+
+#[test]
+fn request_unbond() {
+    let (alice, mut context) = prepare_jar(Product::new());
+
+    let withdrawn = context.contract().withdraw(U32(0), Some(U128(PRINCIPAL / 2))).unwrap();
+    assert_eq!(withdrawn, WithdrawView::new(0, None));
+
+    assert_eq!(
+        context.contract().get_jar(alice.clone(), U32(0)).principal.0,
+        PRINCIPAL / 2
+    );
+    assert_eq!(
+        context.contract().get_pending_unbonds(alice.clone()),
+        vec![UnbondingView {
+            jar_id: U32(0),
+            amount: U128(PRINCIPAL / 2),
+            unlock_at: U64(UNBONDING_PERIOD),
+        }]
+    );
+
+    // Unbonding tokens don't accrue interest
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+    assert_eq!(
+        context.contract().get_total_interest(alice.clone()).amount.total.0,
+        60_000
+    );
+
+    // A new request doesn't restart the pending one
+    expect_panic(&context, "Jar already has pending unbond", || {
+        context.contract().withdraw(U32(0), Some(U128(100_000)));
+    });
+    assert_eq!(
+        context.contract().get_pending_unbonds(alice),
+        vec![UnbondingView {
+            jar_id: U32(0),
+            amount: U128(PRINCIPAL / 2),
+            unlock_at: U64(UNBONDING_PERIOD),
+        }]
+    );
+}
+
+#[test]
+fn finalize_unbond() {
+    let (alice, mut context) = prepare_jar(Product::new().with_withdrawal_fee(WithdrawalFee::Fix(1_000)));
+
+    context.contract().withdraw(U32(0), Some(U128(PRINCIPAL / 2))).unwrap();
+
+    context.set_block_timestamp_in_days(3);
+
+    expect_panic(&context, "Unbonding period is not over yet", || {
+        context.contract().finalize_unbond(U32(0));
+    });
+
+    context.set_block_timestamp_in_days(7);
+
+    let withdrawn = context.contract().finalize_unbond(U32(0)).unwrap();
+    assert_eq!(withdrawn.withdrawn_amount.0, PRINCIPAL / 2 - 1_000);
+    assert_eq!(withdrawn.fee.0, 1_000);
+
+    let jar = context.contract().get_jar(alice.clone(), U32(0));
+    assert_eq!(jar.principal.0, PRINCIPAL / 2);
+    assert!(!jar.is_pending_withdraw);
+    assert!(context.contract().get_pending_unbonds(alice).is_empty());
+
+    expect_panic(&context, "Jar doesn't have pending unbond", || {
+        context.contract().finalize_unbond(U32(0));
+    });
+}
+
+#[test]
+fn finalize_unbond_keeps_empty_flexible_jar() {
+    let (alice, mut context) = prepare_jar(Product::new());
+
+    context.contract().withdraw(U32(0), None).unwrap();
+
+    context.set_block_timestamp_in_days(7);
+
+    let withdrawn = context.contract().finalize_unbond(U32(0)).unwrap();
+    assert_eq!(withdrawn.withdrawn_amount.0, PRINCIPAL);
+
+    // Flexible jars aren't closed when emptied, same as after a regular withdrawal
+    let jars = context.contract().get_jars_for_account(alice);
+    assert_eq!(jars.len(), 1);
+    assert_eq!(jars[0].principal.0, 0);
+}
+
+#[test]
+fn finalize_unbond_keeps_jar_with_interest() {
+    let (alice, mut context) = prepare_jar(Product::new());
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+    context.contract().withdraw(U32(0), None).unwrap();
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR + UNBONDING_PERIOD);
+    context.contract().finalize_unbond(U32(0)).unwrap();
+
+    let jar = context.contract().get_jar(alice.clone(), U32(0));
+    assert_eq!(jar.principal.0, 0);
+    assert_eq!(context.contract().get_total_interest(alice).amount.total.0, 120_000);
+}
+
+#[test]
+fn failed_finalize_unbond() {
+    let (alice, mut context) = prepare_jar(Product::new());
+
+    context.contract().withdraw(U32(0), None).unwrap();
+
+    context.set_block_timestamp_in_days(7);
+    set_test_future_success(false);
+
+    let withdrawn = context.contract().finalize_unbond(U32(0)).unwrap();
+    assert_eq!(withdrawn, WithdrawView::new(0, None));
+
+    assert!(!context.contract().get_jar(alice.clone(), U32(0)).is_pending_withdraw);
+    assert_eq!(
+        context.contract().get_pending_unbonds(alice),
+        vec![UnbondingView {
+            jar_id: U32(0),
+            amount: U128(PRINCIPAL),
+            unlock_at: U64(UNBONDING_PERIOD),
+        }]
+    );
+}
+
+#[test]
+fn cancel_unbond() {
+    let (alice, mut context) = prepare_jar(Product::new());
+
+    expect_panic(&context, "Jar doesn't have pending unbond", || {
+        context.contract().cancel_unbond(U32(0));
+    });
+
+    context.contract().withdraw(U32(0), Some(U128(PRINCIPAL / 2))).unwrap();
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR / 2);
+    context.contract().cancel_unbond(U32(0));
+
+    assert_eq!(context.contract().get_jar(alice.clone(), U32(0)).principal.0, PRINCIPAL);
+    assert!(context.contract().get_pending_unbonds(alice.clone()).is_empty());
+
+    context.set_block_timestamp_in_ms(MS_IN_YEAR);
+    assert_eq!(
+        context.contract().get_total_interest(alice).amount.total.0,
+        30_000 + 60_000
+    );
+}
+
+#[test]
+fn unbonding_jars_are_not_withdrawn_immediately() {
+    let (_, mut context) = prepare_jar(Product::new());
+
+    context.set_block_timestamp_in_days(1);
+
+    assert_eq!(context.contract().withdraw_all(None).unwrap().total_amount.0, 0);
+
+    expect_panic(
+        &context,
+        "Withdraw and call is not supported for products with unbonding period",
+        || {
+            context.contract().withdraw_and_call(U32(0), None, bob(), String::new());
+        },
+    );
+}
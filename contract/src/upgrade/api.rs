@@ -0,0 +1,97 @@
+use near_sdk::{env, env::panic_str, json_types::U64, near, require, NearToken, Promise};
+use sweat_jar_model::{api::UpgradeApi, upgrade::StagedUpgradeView};
+
+use crate::{
+    common::gas_data::GAS_FOR_STATE_MIGRATION,
+    event::{emit, EventKind},
+    internal::assert_gas,
+    upgrade::{decode_code_hash, StagedUpgrade, MIN_UPGRADE_DELAY},
+    Contract, ContractExt,
+};
+
+#[near]
+impl UpgradeApi for Contract {
+    fn stage_upgrade(&mut self, code_hash: String, activate_at: U64, migrate: bool) {
+        self.assert_manager();
+        require!(self.staged_upgrade.is_none(), "Another upgrade is already staged");
+
+        let now = env::block_timestamp_ms();
+        let activate_at = activate_at.0;
+
+        require!(
+            activate_at >= now + MIN_UPGRADE_DELAY,
+            format!("Upgrade can't be activated earlier than {MIN_UPGRADE_DELAY} ms after staging")
+        );
+
+        let upgrade = StagedUpgrade {
+            code_hash: decode_code_hash(&code_hash),
+            staged_at: now,
+            activate_at,
+            migrate,
+        };
+
+        emit(EventKind::StageUpgrade((&upgrade).into()));
+
+        self.staged_upgrade = Some(upgrade);
+    }
+
+    fn cancel_upgrade(&mut self) {
+        self.assert_manager();
+
+        let upgrade = self
+            .staged_upgrade
+            .take()
+            .unwrap_or_else(|| panic_str("There is no staged upgrade"));
+
+        emit(EventKind::CancelUpgrade((&upgrade).into()));
+    }
+
+    fn get_staged_upgrade(&self) -> Option<StagedUpgradeView> {
+        self.staged_upgrade.as_ref().map(Into::into)
+    }
+}
+
+#[near]
+impl Contract {
+    /// Deploys the code of the staged upgrade once its timelock is expired.
+    /// The wasm is passed as raw input of the call and must match the staged code hash.
+    pub fn execute_upgrade(&mut self) -> Promise {
+        self.assert_manager();
+
+        let upgrade = self
+            .staged_upgrade
+            .clone()
+            .unwrap_or_else(|| panic_str("There is no staged upgrade"));
+
+        require!(
+            env::block_timestamp_ms() >= upgrade.activate_at,
+            format!("Upgrade can't be executed before {}", upgrade.activate_at)
+        );
+
+        let code = env::input().unwrap_or_else(|| panic_str("Contract code is required"));
+
+        require!(
+            env::sha256_array(&code) == upgrade.code_hash,
+            "Code hash doesn't match the staged upgrade"
+        );
+
+        self.staged_upgrade = None;
+
+        emit(EventKind::ExecuteUpgrade((&upgrade).into()));
+
+        let promise = Promise::new(env::current_account_id()).deploy_contract(code);
+
+        if !upgrade.migrate {
+            return promise;
+        }
+
+        assert_gas(GAS_FOR_STATE_MIGRATION.as_gas(), || "Failed to execute upgrade");
+
+        promise.function_call(
+            "migrate_state".to_string(),
+            b"{}".to_vec(),
+            NearToken::from_yoctonear(0),
+            GAS_FOR_STATE_MIGRATION,
+        )
+    }
+}
@@ -0,0 +1,53 @@
+use near_sdk::{env::panic_str, near, CryptoHash};
+use sweat_jar_model::{upgrade::StagedUpgradeView, MS_IN_DAY};
+
+use crate::common::{to_hex, Duration, Timestamp};
+
+pub mod api;
+mod tests;
+
+/// Minimal time users have to review the staged code before it's deployed.
+pub(crate) const MIN_UPGRADE_DELAY: Duration = 2 * MS_IN_DAY;
+
+#[near]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StagedUpgrade {
+    /// sha256 of the wasm to deploy.
+    pub code_hash: CryptoHash,
+    pub staged_at: Timestamp,
+    pub activate_at: Timestamp,
+    /// Whether `migrate_state` is called right after the code is deployed.
+    pub migrate: bool,
+}
+
+impl From<&StagedUpgrade> for StagedUpgradeView {
+    fn from(value: &StagedUpgrade) -> Self {
+        Self {
+            code_hash: encode_code_hash(&value.code_hash),
+            staged_at: value.staged_at.into(),
+            activate_at: value.activate_at.into(),
+            migrate: value.migrate,
+        }
+    }
+}
+
+pub(crate) fn encode_code_hash(hash: &CryptoHash) -> String {
+    to_hex(hash)
+}
+
+pub(crate) fn decode_code_hash(hash: &str) -> CryptoHash {
+    let error = || panic_str("Code hash must be a hex encoded sha256");
+
+    if hash.len() != 2 * size_of::<CryptoHash>() || !hash.is_ascii() {
+        error();
+    }
+
+    let mut result = CryptoHash::default();
+
+    for (byte, chunk) in result.iter_mut().zip(hash.as_bytes().chunks(2)) {
+        let chunk = std::str::from_utf8(chunk).unwrap_or_else(|_| error());
+        *byte = u8::from_str_radix(chunk, 16).unwrap_or_else(|_| error());
+    }
+
+    result
+}
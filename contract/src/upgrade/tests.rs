@@ -0,0 +1,139 @@
+#![cfg(test)]
+
+use near_sdk::{json_types::U64, test_utils::test_env::alice};
+use sweat_jar_model::{api::UpgradeApi, upgrade::StagedUpgradeView, MS_IN_DAY};
+
+use crate::{
+    common::tests::Context,
+    test_utils::{admin, expect_panic},
+    upgrade::{decode_code_hash, encode_code_hash, MIN_UPGRADE_DELAY},
+};
+
+const CODE: &[u8] = b"new contract code";
+
+fn prepare_context() -> Context {
+    let mut context = Context::new(admin());
+    context.switch_account(admin());
+    context
+}
+
+fn stage(context: &Context, activate_at: u64) {
+    context
+        .contract()
+        .stage_upgrade(sha256::digest(CODE), U64(activate_at), true);
+}
+
+#[test]
+fn code_hash_encoding() {
+    let hash = sha256::digest(CODE);
+
+    assert_eq!(encode_code_hash(&decode_code_hash(&hash)), hash);
+
+    expect_panic(&(), "Code hash must be a hex encoded sha256", || {
+        decode_code_hash("not a hash");
+    });
+
+    expect_panic(&(), "Code hash must be a hex encoded sha256", || {
+        decode_code_hash(&"z".repeat(64));
+    });
+}
+
+#[test]
+fn stage_and_cancel_upgrade() {
+    let mut context = prepare_context();
+    context.set_block_timestamp_in_days(1);
+
+    stage(&context, 3 * MS_IN_DAY);
+
+    assert_eq!(
+        context.contract().get_staged_upgrade(),
+        Some(StagedUpgradeView {
+            code_hash: sha256::digest(CODE),
+            staged_at: U64(MS_IN_DAY),
+            activate_at: U64(3 * MS_IN_DAY),
+            migrate: true,
+        })
+    );
+
+    expect_panic(&context, "Another upgrade is already staged", || {
+        stage(&context, 3 * MS_IN_DAY);
+    });
+
+    context.contract().cancel_upgrade();
+
+    assert!(context.contract().get_staged_upgrade().is_none());
+
+    expect_panic(&context, "There is no staged upgrade", || {
+        context.contract().cancel_upgrade();
+    });
+}
+
+#[test]
+fn stage_upgrade_without_delay() {
+    let context = prepare_context();
+
+    expect_panic(&context, "Upgrade can't be activated earlier than", || {
+        stage(&context, MIN_UPGRADE_DELAY - 1);
+    });
+}
+
+#[test]
+fn upgrade_not_by_manager() {
+    let mut context = prepare_context();
+    stage(&context, MIN_UPGRADE_DELAY);
+
+    context.switch_account(alice());
+
+    expect_panic(&context, "Can be performed only by admin", || {
+        stage(&context, MIN_UPGRADE_DELAY);
+    });
+
+    expect_panic(&context, "Can be performed only by admin", || {
+        context.contract().cancel_upgrade();
+    });
+
+    expect_panic(&context, "Can be performed only by admin", || {
+        context.contract().execute_upgrade();
+    });
+}
+
+#[test]
+fn execute_upgrade_before_activation() {
+    let mut context = prepare_context();
+    stage(&context, MIN_UPGRADE_DELAY);
+
+    context.set_input(CODE.to_vec());
+    context.set_block_timestamp_in_ms(MIN_UPGRADE_DELAY - 1);
+
+    expect_panic(&context, "Upgrade can't be executed before", || {
+        context.contract().execute_upgrade();
+    });
+
+    assert!(context.contract().get_staged_upgrade().is_some());
+}
+
+#[test]
+fn execute_upgrade_with_other_code() {
+    let mut context = prepare_context();
+    stage(&context, MIN_UPGRADE_DELAY);
+
+    context.set_input(b"other contract code".to_vec());
+    context.set_block_timestamp_in_ms(MIN_UPGRADE_DELAY);
+
+    expect_panic(&context, "Code hash doesn't match the staged upgrade", || {
+        context.contract().execute_upgrade();
+    });
+}
+
+#[test]
+fn execute_upgrade() {
+    let mut context = prepare_context();
+    stage(&context, MIN_UPGRADE_DELAY);
+
+    context.set_input(CODE.to_vec());
+    context.set_block_timestamp_in_ms(MIN_UPGRADE_DELAY);
+
+    context.contract().execute_upgrade();
+
+    assert!(context.contract().get_staged_upgrade().is_none());
+}
@@ -1,13 +1,13 @@
 use near_sdk::{
     ext_contract,
     json_types::U128,
-    near_bindgen,
+    near_bindgen, require,
     serde::{Deserialize, Serialize},
-    PromiseOrValue,
+    serde_json, PromiseOrValue, PromiseResult,
 };
 use sweat_jar_model::{
     api::WithdrawApi,
-    jar::{JarId, JarIdView},
+    jar::{JarId, JarIdView, LockOperation},
     withdraw::{BulkWithdrawView, Fee, WithdrawView},
     TokenAmount, JAR_BATCH_SIZE,
 };
@@ -28,16 +28,55 @@ use crate::ft_interface::FungibleTokenInterface;
 use crate::{
     assert::{assert_is_liquidable, assert_not_locked, assert_sufficient_balance},
     env,
-    event::{emit, EventKind},
+    event::{emit, emit_fee_split, EventKind, WithdrawAndCallData},
     jar::model::Jar,
-    product::model::WithdrawalFee,
     score::AccountScore,
     AccountId, Contract, ContractExt, Product,
 };
 
 impl Contract {
+    /// Jars of products with unbonding period can't be withdrawn immediately, so they are skipped here.
     fn can_be_withdrawn(jar: &Jar, product: &Product, now: u64) -> bool {
-        !jar.is_pending_withdraw && jar.is_liquidable(product, now) && !jar.is_empty()
+        !jar.is_pending_withdraw
+            && product.unbonding_period.is_none()
+            && jar.is_liquidable(product, now)
+            && !jar.is_empty()
+    }
+
+    /// Withdraws `amount` from the jar and locks it until the transfer is resolved.
+    /// Returns the jar state before withdrawal, withdrawn amount and whether the jar should be closed.
+    fn lock_jar_for_withdraw(
+        &mut self,
+        account_id: &AccountId,
+        jar_id: JarId,
+        amount: Option<U128>,
+        operation: LockOperation,
+    ) -> (Jar, TokenAmount, bool) {
+        let jar = self.get_jar_internal(account_id, jar_id).clone();
+
+        assert_not_locked(&jar);
+
+        let amount = amount.map_or(jar.principal, |value| value.0);
+
+        assert_sufficient_balance(&jar, amount);
+
+        let now = env::block_timestamp_ms();
+        let product = self.get_product(&jar.product_id);
+
+        assert_is_liquidable(&jar, product, now);
+
+        let score = self
+            .get_score(account_id)
+            .map(AccountScore::claimable_score)
+            .unwrap_or_default();
+
+        let mut withdrawn_jar = jar.withdrawn(&score, product, amount, now);
+        let close_jar = withdrawn_jar.should_be_closed(&score, product, now);
+
+        withdrawn_jar.lock(operation, now);
+        *self.get_jar_mut_internal(&jar.account_id, jar.id) = withdrawn_jar;
+
+        (jar, amount, close_jar)
     }
 }
 
@@ -54,6 +93,22 @@ pub trait WithdrawCallbacks {
     ) -> WithdrawView;
 
     fn after_bulk_withdraw(&mut self, account_id: AccountId, jars: Vec<JarWithdraw>) -> BulkWithdrawView;
+
+    fn after_withdraw_and_call(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+    ) -> PromiseOrValue<WithdrawView>;
+
+    fn after_withdraw_and_call_fee(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        used_amount: TokenAmount,
+        fee: Fee,
+    ) -> WithdrawView;
 }
 
 #[near_bindgen]
@@ -63,29 +118,14 @@ impl WithdrawApi for Contract {
         self.assert_account_is_not_migrating(&account_id);
         self.migrate_account_if_needed(&account_id);
 
-        let jar = self.get_jar_internal(&account_id, jar_id.0).clone();
-
-        assert_not_locked(&jar);
-
-        let amount = amount.map_or(jar.principal, |value| value.0);
-
-        assert_sufficient_balance(&jar, amount);
-
-        let now = env::block_timestamp_ms();
-        let product = self.get_product(&jar.product_id);
-
-        assert_is_liquidable(&jar, &product, now);
-
-        let score = self
-            .get_score(&account_id)
-            .map(AccountScore::claimable_score)
-            .unwrap_or_default();
+        let product_id = self.get_jar_internal(&account_id, jar_id.0).product_id;
 
-        let mut withdrawn_jar = jar.withdrawn(&score, &product, amount, now);
-        let close_jar = withdrawn_jar.should_be_closed(&score, &product, now);
+        if self.get_product(&product_id).unbonding_period.is_some() {
+            return PromiseOrValue::Value(self.request_unbond(&account_id, jar_id.0, amount));
+        }
 
-        withdrawn_jar.lock();
-        *self.get_jar_mut_internal(&jar.account_id, jar.id) = withdrawn_jar;
+        let (jar, amount, close_jar) =
+            self.lock_jar_for_withdraw(&account_id, jar_id.0, amount, LockOperation::Withdraw);
 
         self.transfer_withdraw(&account_id, amount, &jar, close_jar)
     }
@@ -112,7 +152,7 @@ impl WithdrawApi for Contract {
             .clone()
             .into_iter()
             .filter_map(|jar| {
-                let product = self.get_product(&jar.product_id);
+                let product = self.get_product(&jar.product_id).clone();
 
                 if !Self::can_be_withdrawn(&jar, &product, now) {
                     return None;
@@ -133,18 +173,19 @@ impl WithdrawApi for Contract {
             .into_iter()
             .map(|(jar, product)| {
                 let amount = jar.principal;
+                let fee = Self::get_fee(&product, &jar);
 
                 let mut withdrawn_jar = jar.withdrawn(&score, &product, amount, now);
                 let should_be_closed = withdrawn_jar.should_be_closed(&score, &product, now);
 
-                withdrawn_jar.lock();
+                withdrawn_jar.lock(LockOperation::WithdrawAll, now);
                 *self.get_jar_mut_internal(&jar.account_id, jar.id) = withdrawn_jar;
 
                 JarWithdraw {
                     jar,
                     should_be_closed,
                     amount,
-                    fee: None,
+                    fee,
                 }
             })
             .collect();
@@ -155,6 +196,30 @@ impl WithdrawApi for Contract {
 
         self.transfer_bulk_withdraw(&account_id, jars)
     }
+
+    fn withdraw_and_call(
+        &mut self,
+        jar_id: JarIdView,
+        amount: Option<U128>,
+        receiver_id: AccountId,
+        msg: String,
+    ) -> PromiseOrValue<WithdrawView> {
+        let account_id = env::predecessor_account_id();
+        self.assert_account_is_not_migrating(&account_id);
+        self.migrate_account_if_needed(&account_id);
+
+        let product_id = self.get_jar_internal(&account_id, jar_id.0).product_id;
+
+        require!(
+            self.get_product(&product_id).unbonding_period.is_none(),
+            "Withdraw and call is not supported for products with unbonding period"
+        );
+
+        let (jar, amount, close_jar) =
+            self.lock_jar_for_withdraw(&account_id, jar_id.0, amount, LockOperation::WithdrawAndCall);
+
+        self.transfer_withdraw_and_call(&account_id, amount, &jar, close_jar, receiver_id, msg)
+    }
 }
 
 impl Contract {
@@ -188,6 +253,7 @@ impl Contract {
             withdrawal_result.fee,
             withdrawal_result.withdrawn_amount,
         )));
+        emit_fee_split(vec![(jar_id, &withdrawal_result)]);
 
         withdrawal_result
     }
@@ -213,6 +279,7 @@ impl Contract {
         }
 
         let mut event_data = vec![];
+        let mut jar_ids = vec![];
 
         for withdraw in jars {
             if withdraw.should_be_closed {
@@ -221,36 +288,132 @@ impl Contract {
                 self.get_jar_mut_internal(&account_id, withdraw.jar.id).unlock();
             }
 
-            let jar_result = WithdrawView::new(withdraw.amount, self.make_fee(withdraw.fee));
+            let product = self.get_product(&withdraw.jar.product_id);
+            let jar_result = WithdrawView::new(withdraw.amount, self.make_fee(product, withdraw.fee));
 
             event_data.push((withdraw.jar.id, jar_result.fee, jar_result.withdrawn_amount));
+            jar_ids.push(withdraw.jar.id);
 
             withdrawal_result.total_amount.0 += jar_result.withdrawn_amount.0;
             withdrawal_result.jars.push(jar_result);
         }
 
         emit(EventKind::WithdrawAll(event_data));
+        emit_fee_split(jar_ids.into_iter().zip(withdrawal_result.jars.iter()).collect());
 
         withdrawal_result
     }
 
-    fn get_fee(product: &Product, jar: &Jar) -> Option<TokenAmount> {
-        let fee = product.withdrawal_fee.as_ref()?;
+    /// `used_amount` is the amount of tokens used by the receiver of `ft_transfer_call`.
+    /// It is `None` if the transfer failed.
+    ///
+    /// The fee is withheld by the contract until the receiver reports the used amount.
+    /// The part of the withdrawal refunded by the receiver goes back to the jar without a fee,
+    /// so the fee is charged only on the rest. If everything is refunded, no fee is charged.
+    /// The jar stays locked until the charged fee is transferred.
+    pub(crate) fn after_withdraw_and_call_internal(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        used_amount: Option<TokenAmount>,
+    ) -> PromiseOrValue<WithdrawView> {
+        let Some(used_amount) = used_amount else {
+            let jar = self.get_jar_mut_internal(&account_id, withdraw.jar.id);
+            jar.principal += withdraw.amount;
+            jar.unlock();
 
-        let amount = match fee {
-            WithdrawalFee::Fix(amount) => *amount,
-            WithdrawalFee::Percent(percent) => percent * jar.principal,
+            return PromiseOrValue::Value(WithdrawView::new(0, None));
         };
 
-        amount.into()
+        let product = self.get_product(&withdraw.jar.product_id);
+
+        let charged_fee = withdraw.fee.map(|withheld_fee| {
+            // Everything is used, so the whole withheld fee is charged without rounding errors.
+            if used_amount == withdraw.amount - withheld_fee {
+                return withheld_fee;
+            }
+
+            Self::get_fee_for_net_amount(product, &withdraw.jar, used_amount)
+                .map_or(0, |charged| charged.min(withheld_fee))
+        });
+
+        let fee = self.make_fee(product, charged_fee);
+
+        match fee.filter(|fee| fee.amount() > 0) {
+            Some(fee) => self.transfer_withdraw_and_call_fee(account_id, withdraw, receiver_id, used_amount, fee),
+            None => PromiseOrValue::Value(self.after_withdraw_and_call_fee_internal(
+                account_id,
+                withdraw,
+                receiver_id,
+                used_amount,
+                None,
+                true,
+            )),
+        }
     }
 
-    fn make_fee(&self, amount: Option<TokenAmount>) -> Option<Fee> {
-        Fee {
-            beneficiary_id: self.fee_account_id.clone(),
-            amount: amount?,
+    /// Settles the jar after the fee for `withdraw_and_call` is transferred.
+    /// If the fee transfer failed, the fee is returned to the jar, so it isn't reported as paid.
+    pub(crate) fn after_withdraw_and_call_fee_internal(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        used_amount: TokenAmount,
+        fee: Option<Fee>,
+        is_fee_transferred: bool,
+    ) -> WithdrawView {
+        let jar_id = withdraw.jar.id;
+
+        let fee = fee.filter(|_| is_fee_transferred);
+        let charged_fee = fee.as_ref().map_or(0, Fee::amount);
+        let refund = withdraw.amount - used_amount - charged_fee;
+
+        if withdraw.should_be_closed && refund == 0 {
+            self.delete_jar(&account_id, jar_id);
+        } else {
+            let jar = self.get_jar_mut_internal(&account_id, jar_id);
+            jar.principal += refund;
+            jar.unlock();
         }
-        .into()
+
+        let withdrawal_result = WithdrawView::new(used_amount + charged_fee, fee);
+
+        emit(EventKind::WithdrawAndCall(WithdrawAndCallData {
+            id: jar_id,
+            receiver_id,
+            fee: withdrawal_result.fee,
+            amount: withdrawal_result.withdrawn_amount,
+            refund: refund.into(),
+        }));
+        emit_fee_split(vec![(jar_id, &withdrawal_result)]);
+
+        withdrawal_result
+    }
+
+    fn get_fee(product: &Product, jar: &Jar) -> Option<TokenAmount> {
+        Self::get_fee_for_amount(product, jar, jar.principal)
+    }
+
+    /// Scheduled fees are evaluated against the age of the jar at the moment of withdrawal.
+    pub(crate) fn get_fee_for_amount(product: &Product, jar: &Jar, amount: TokenAmount) -> Option<TokenAmount> {
+        let fee = product.withdrawal_fee.as_ref()?;
+        let held = env::block_timestamp_ms().saturating_sub(jar.created_at);
+
+        fee.get_amount(amount, held).into()
+    }
+
+    /// The fee for a withdrawal that leaves `net` tokens after the fee is deducted.
+    fn get_fee_for_net_amount(product: &Product, jar: &Jar, net: TokenAmount) -> Option<TokenAmount> {
+        let fee = product.withdrawal_fee.as_ref()?;
+        let held = env::block_timestamp_ms().saturating_sub(jar.created_at);
+
+        fee.get_amount_for_net(net, held).into()
+    }
+
+    pub(crate) fn make_fee(&self, product: &Product, amount: Option<TokenAmount>) -> Option<Fee> {
+        product.split_fee(&self.fee_account_id, amount?).into()
     }
 }
 
@@ -265,16 +428,18 @@ impl Contract {
         close_jar: bool,
     ) -> PromiseOrValue<WithdrawView> {
         let product = self.get_product(&jar.product_id);
-        let fee = Self::get_fee(&product, jar);
+        let fee = Self::get_fee(product, jar);
+
+        let fee = self.make_fee(product, fee);
 
         self.ft_contract()
-            .ft_transfer(account_id, amount, "withdraw", &self.make_fee(fee))
+            .ft_transfer(account_id, amount, "withdraw", &fee)
             .then(Self::after_withdraw_call(
                 account_id.clone(),
                 jar.id,
                 close_jar,
                 amount,
-                &self.make_fee(fee),
+                &fee,
             ))
             .into()
     }
@@ -284,18 +449,14 @@ impl Contract {
         account_id: &AccountId,
         jars: Vec<JarWithdraw>,
     ) -> PromiseOrValue<BulkWithdrawView> {
-        let total_fee: TokenAmount = jars
+        let total_fee = jars
             .iter()
-            .filter_map(|j| {
-                let product = self.get_product(&j.jar.product_id);
-                Self::get_fee(&product, &j.jar)
+            .filter_map(|j| self.make_fee(self.get_product(&j.jar.product_id), j.fee))
+            .reduce(|mut total, fee| {
+                total.merge(fee);
+                total
             })
-            .sum();
-
-        let total_fee = match total_fee {
-            0 => None,
-            _ => self.make_fee(total_fee.into()),
-        };
+            .filter(|fee| fee.amount() > 0);
 
         let total_amount = jars.iter().map(|j| j.amount).sum();
 
@@ -311,6 +472,72 @@ impl Contract {
             .into()
     }
 
+    fn transfer_withdraw_and_call(
+        &mut self,
+        account_id: &AccountId,
+        amount: TokenAmount,
+        jar: &Jar,
+        close_jar: bool,
+        receiver_id: AccountId,
+        msg: String,
+    ) -> PromiseOrValue<WithdrawView> {
+        let product = self.get_product(&jar.product_id);
+        let fee = Self::get_fee_for_amount(product, jar, amount);
+
+        // The callback transfers the fee, so it needs gas for a transfer to each beneficiary
+        // and for the callback settling the jar after the fee transfer.
+        let fee_gas = self.make_fee(product, fee).map_or(0, |fee| {
+            crate::common::gas_data::GAS_FOR_FT_TRANSFER.as_gas() * fee.transfers.len() as u64
+                + crate::common::gas_data::GAS_FOR_AFTER_WITHDRAW.as_gas()
+        });
+        let callback_gas =
+            near_sdk::Gas::from_gas(crate::common::gas_data::GAS_FOR_AFTER_WITHDRAW_AND_CALL.as_gas() + fee_gas);
+
+        crate::internal::assert_gas(
+            crate::common::gas_data::GAS_FOR_FT_TRANSFER_CALL.as_gas() + callback_gas.as_gas(),
+            || format!("transfer_withdraw_and_call. Jar: {}", jar.id),
+        );
+
+        self.ft_contract()
+            .ft_transfer_call(
+                &receiver_id,
+                amount - fee.unwrap_or_default(),
+                "withdraw_and_call",
+                &msg,
+                crate::common::gas_data::GAS_FOR_FT_TRANSFER_CALL.as_tgas(),
+            )
+            .then(Self::after_withdraw_and_call_call(
+                account_id.clone(),
+                JarWithdraw {
+                    jar: jar.clone(),
+                    should_be_closed: close_jar,
+                    amount,
+                    fee,
+                },
+                receiver_id,
+                callback_gas,
+            ))
+            .into()
+    }
+
+    fn transfer_withdraw_and_call_fee(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        used_amount: TokenAmount,
+        fee: Fee,
+    ) -> PromiseOrValue<WithdrawView> {
+        self.ft_contract()
+            .ft_transfer_fee(&fee, "withdraw_and_call")
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(crate::common::gas_data::GAS_FOR_AFTER_WITHDRAW)
+                    .after_withdraw_and_call_fee(account_id, withdraw, receiver_id, used_amount, fee),
+            )
+            .into()
+    }
+
     fn after_withdraw_call(
         account_id: AccountId,
         jar_id: JarId,
@@ -328,6 +555,17 @@ impl Contract {
             .with_static_gas(crate::common::gas_data::GAS_FOR_BULK_AFTER_WITHDRAW)
             .after_bulk_withdraw(account_id, jars)
     }
+
+    fn after_withdraw_and_call_call(
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        gas: near_sdk::Gas,
+    ) -> near_sdk::Promise {
+        ext_self::ext(env::current_account_id())
+            .with_static_gas(gas)
+            .after_withdraw_and_call(account_id, withdraw, receiver_id)
+    }
 }
 
 #[cfg(test)]
@@ -340,14 +578,14 @@ impl Contract {
         close_jar: bool,
     ) -> PromiseOrValue<WithdrawView> {
         let product = self.get_product(&jar.product_id);
-        let fee = Self::get_fee(&product, jar);
+        let fee = self.make_fee(product, Self::get_fee(product, jar));
 
         let withdrawn = self.after_withdraw_internal(
             account_id.clone(),
             jar.id,
             close_jar,
             amount,
-            self.make_fee(fee),
+            fee,
             crate::common::test_data::get_test_future_success(),
         );
 
@@ -367,6 +605,54 @@ impl Contract {
 
         PromiseOrValue::Value(withdrawn)
     }
+
+    fn transfer_withdraw_and_call(
+        &mut self,
+        account_id: &AccountId,
+        amount: TokenAmount,
+        jar: &Jar,
+        close_jar: bool,
+        receiver_id: AccountId,
+        _msg: String,
+    ) -> PromiseOrValue<WithdrawView> {
+        let product = self.get_product(&jar.product_id);
+        let fee = Self::get_fee_for_amount(product, jar, amount);
+
+        let used_amount = crate::common::test_data::get_test_future_success()
+            .then(|| amount - fee.unwrap_or_default() - crate::common::test_data::get_test_transfer_call_refund());
+
+        self.after_withdraw_and_call_internal(
+            account_id.clone(),
+            JarWithdraw {
+                jar: jar.clone(),
+                should_be_closed: close_jar,
+                amount,
+                fee,
+            },
+            receiver_id,
+            used_amount,
+        )
+    }
+
+    fn transfer_withdraw_and_call_fee(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        used_amount: TokenAmount,
+        fee: Fee,
+    ) -> PromiseOrValue<WithdrawView> {
+        let withdrawn = self.after_withdraw_and_call_fee_internal(
+            account_id,
+            withdraw,
+            receiver_id,
+            used_amount,
+            Some(fee),
+            crate::common::test_data::get_test_future_success(),
+        );
+
+        PromiseOrValue::Value(withdrawn)
+    }
 }
 
 #[near_bindgen]
@@ -395,4 +681,47 @@ impl WithdrawCallbacks for Contract {
     fn after_bulk_withdraw(&mut self, account_id: AccountId, jars: Vec<JarWithdraw>) -> BulkWithdrawView {
         self.after_bulk_withdraw_internal(account_id, jars, is_promise_success())
     }
+
+    #[private]
+    fn after_withdraw_and_call(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+    ) -> PromiseOrValue<WithdrawView> {
+        self.after_withdraw_and_call_internal(account_id, withdraw, receiver_id, transfer_call_used_amount())
+    }
+
+    #[private]
+    fn after_withdraw_and_call_fee(
+        &mut self,
+        account_id: AccountId,
+        withdraw: JarWithdraw,
+        receiver_id: AccountId,
+        used_amount: TokenAmount,
+        fee: Fee,
+    ) -> WithdrawView {
+        self.after_withdraw_and_call_fee_internal(
+            account_id,
+            withdraw,
+            receiver_id,
+            used_amount,
+            Some(fee),
+            is_promise_success(),
+        )
+    }
+}
+
+/// `ft_transfer_call` returns the amount of tokens used by the receiver.
+/// The rest of the tokens are refunded to the sender.
+#[mutants::skip] // Covered by integration tests
+fn transfer_call_used_amount() -> Option<TokenAmount> {
+    if !is_promise_success() {
+        return None;
+    }
+
+    match env::promise_result(0) {
+        PromiseResult::Successful(value) => serde_json::from_slice::<U128>(&value).ok().map(|used| used.0),
+        PromiseResult::Failed => None,
+    }
 }
@@ -1,15 +1,25 @@
 #![cfg(test)]
 
-use near_sdk::{json_types::U128, test_utils::test_env::alice, AccountId};
+use near_sdk::{
+    json_types::U128,
+    test_utils::test_env::{alice, bob},
+    AccountId,
+};
 use sweat_jar_model::{
     api::{ClaimApi, JarApi, WithdrawApi},
-    UDecimal, MS_IN_YEAR, U32,
+    jar::LockOperation,
+    withdraw::{Fee, FeeTransfer, WithdrawView},
+    UDecimal, MS_IN_DAY, MS_IN_YEAR, U32,
 };
 
 use crate::{
-    common::{test_data::set_test_future_success, tests::Context, Timestamp},
+    common::{
+        test_data::{set_test_future_success, set_test_transfer_call_refund},
+        tests::Context,
+        Timestamp,
+    },
     jar::model::Jar,
-    product::model::{Apy, Product, WithdrawalFee},
+    product::model::{Apy, FeeScheduleStep, Product, WithdrawalFee},
     test_utils::{admin, expect_panic, UnwrapPromise, PRINCIPAL},
     withdraw::api::JarWithdraw,
 };
@@ -222,6 +232,91 @@ fn product_with_fixed_fee() {
     assert_eq!(jar.principal, U128(initial_principal - withdraw_amount));
 }
 
+fn fee_transfer(beneficiary_id: &str, amount: u128) -> FeeTransfer {
+    FeeTransfer {
+        beneficiary_id: beneficiary_id.parse().unwrap(),
+        amount: U128(amount),
+    }
+}
+
+#[test]
+fn product_with_split_fee() {
+    let product = Product::new()
+        .with_withdrawal_fee(WithdrawalFee::Fix(10))
+        .fee_split(&[("treasury", UDecimal::new(7, 1)), ("rewards", UDecimal::new(3, 1))]);
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+    context.switch_account(&alice);
+
+    let withdraw = context
+        .contract()
+        .withdraw(U32(reference_jar.id), Some(U128(100_000)))
+        .unwrap();
+
+    assert_eq!(withdraw.withdrawn_amount, U128(100_000 - 10));
+    assert_eq!(withdraw.fee, U128(10));
+    assert_eq!(
+        withdraw.fee_split,
+        vec![fee_transfer("treasury", 7), fee_transfer("rewards", 3)]
+    );
+}
+
+#[test]
+fn split_fee_remainder_goes_to_last_beneficiary() {
+    let product = Product::new().with_withdrawal_fee(WithdrawalFee::Fix(10)).fee_split(&[
+        ("treasury", UDecimal::new(333, 3)),
+        ("rewards", UDecimal::new(333, 3)),
+        ("team", UDecimal::new(334, 3)),
+    ]);
+
+    let fee = product.split_fee(&"fee".parse().unwrap(), 10);
+
+    assert_eq!(fee.amount(), 10);
+    assert_eq!(
+        fee.transfers,
+        vec![
+            fee_transfer("treasury", 3),
+            fee_transfer("rewards", 3),
+            fee_transfer("team", 4)
+        ]
+    );
+}
+
+#[test]
+fn product_with_fee_schedule() {
+    let product = Product::new()
+        .flexible()
+        .with_withdrawal_fee(WithdrawalFee::Schedule(vec![
+            FeeScheduleStep {
+                until: MS_IN_DAY * 30,
+                fee: UDecimal::new(5, 2),
+            },
+            FeeScheduleStep {
+                until: MS_IN_DAY * 90,
+                fee: UDecimal::new(1, 2),
+            },
+        ]));
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.switch_account(&alice);
+
+    let withdraw_amount = 100_000;
+
+    // Percent fees are calculated from the principal of the jar before withdrawal
+    for (day, fee) in [(10, 50_000), (30, 9_000), (89, 8_000), (90, 0)] {
+        context.set_block_timestamp_in_days(day);
+
+        let withdraw = context
+            .contract()
+            .withdraw(U32(reference_jar.id), Some(U128(withdraw_amount)))
+            .unwrap();
+
+        assert_eq!(withdraw.fee, U128(fee));
+        assert_eq!(withdraw.withdrawn_amount, U128(withdraw_amount - fee));
+    }
+}
+
 #[test]
 fn product_with_percent_fee() {
     let fee_value = UDecimal::new(5, 4);
@@ -328,7 +423,7 @@ fn withdraw_from_locked_jar() {
     let product = Product::new().apy(Apy::Constant(UDecimal::new(1, 0)));
     let mut jar = Jar::new(0).principal(MS_IN_YEAR as u128);
 
-    jar.lock();
+    jar.lock(LockOperation::Withdraw, 0);
 
     let alice = alice();
     let admin = admin();
@@ -429,3 +524,227 @@ fn batch_withdraw_all() {
 
     assert_eq!(jars, [0, 7, 2, 6, 4,]);
 }
+
+#[test]
+fn withdraw_all_with_fee() {
+    let alice = alice();
+    let admin = admin();
+
+    let fixed_fee = 10;
+    let percent_fee = UDecimal::new(5, 4);
+
+    let fixed_fee_product = Product::new()
+        .id("fixed_fee_product")
+        .with_withdrawal_fee(WithdrawalFee::Fix(fixed_fee));
+    let percent_fee_product = Product::new()
+        .id("percent_fee_product")
+        .with_withdrawal_fee(WithdrawalFee::Percent(percent_fee.clone()));
+    let no_fee_product = Product::new();
+
+    let fixed_fee_jar = Jar::new(0).product_id(&fixed_fee_product.id).principal(PRINCIPAL);
+    let percent_fee_jar = Jar::new(1).product_id(&percent_fee_product.id).principal(PRINCIPAL);
+    let no_fee_jar = Jar::new(2).product_id(&no_fee_product.id).principal(PRINCIPAL);
+
+    let mut context = Context::new(admin)
+        .with_products(&[fixed_fee_product, percent_fee_product, no_fee_product])
+        .with_jars(&[fixed_fee_jar, percent_fee_jar, no_fee_jar]);
+
+    context.set_block_timestamp_in_days(366);
+
+    context.switch_account(&alice);
+
+    context.contract().claim_total(None);
+
+    let withdrawn = context.contract().withdraw_all(None).unwrap();
+
+    let percent_fee = percent_fee * PRINCIPAL;
+
+    assert_eq!(
+        withdrawn.jars,
+        vec![
+            WithdrawView {
+                withdrawn_amount: U128(PRINCIPAL - fixed_fee),
+                fee: U128(fixed_fee),
+                fee_split: vec![],
+            },
+            WithdrawView {
+                withdrawn_amount: U128(PRINCIPAL - percent_fee),
+                fee: U128(percent_fee),
+                fee_split: vec![],
+            },
+            WithdrawView {
+                withdrawn_amount: U128(PRINCIPAL),
+                fee: U128(0),
+                fee_split: vec![],
+            },
+        ]
+    );
+
+    assert_eq!(withdrawn.total_amount.0, PRINCIPAL * 3 - fixed_fee - percent_fee);
+}
+
+#[test]
+fn withdraw_and_call() {
+    let fee = 10;
+    let product = Product::new().with_withdrawal_fee(WithdrawalFee::Fix(fee));
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+    context.switch_account(&alice);
+
+    context.contract().claim_total(None);
+
+    let withdrawn = context
+        .contract()
+        .withdraw_and_call(U32(reference_jar.id), None, bob(), "stake".to_string())
+        .unwrap();
+
+    assert_eq!(withdrawn.withdrawn_amount, U128(reference_jar.principal - fee));
+    assert_eq!(withdrawn.fee, U128(fee));
+
+    assert!(context.contract().get_jars_for_account(alice).is_empty());
+}
+
+#[test]
+fn withdraw_and_call_with_refund() {
+    let fee = 10;
+    let refund = 300_000;
+    let product = Product::new().with_withdrawal_fee(WithdrawalFee::Fix(fee));
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+    context.switch_account(&alice);
+
+    set_test_transfer_call_refund(refund);
+
+    let withdraw_amount = 500_000;
+    let withdrawn = context
+        .contract()
+        .withdraw_and_call(U32(reference_jar.id), Some(U128(withdraw_amount)), bob(), String::new())
+        .unwrap();
+
+    assert_eq!(withdrawn.withdrawn_amount, U128(withdraw_amount - fee - refund));
+    assert_eq!(withdrawn.fee, U128(fee));
+
+    let jar = context.contract().get_jar_internal(&alice, reference_jar.id);
+
+    assert_eq!(jar.principal, reference_jar.principal - withdraw_amount + refund);
+    assert!(!jar.is_pending_withdraw);
+}
+
+#[test]
+fn withdraw_and_call_with_percent_fee_and_refund() {
+    let product = Product::new().with_withdrawal_fee(WithdrawalFee::Percent(UDecimal::new(1, 2)));
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+    context.switch_account(&alice);
+
+    set_test_transfer_call_refund(300_000);
+
+    // 5_000 of fee is withheld from 500_000, so the receiver gets 495_000 and uses 195_000.
+    // The fee is charged only on the gross amount behind 195_000: 195_000 / 0.99 * 0.01 = 1_969.
+    let withdrawn = context
+        .contract()
+        .withdraw_and_call(U32(reference_jar.id), Some(U128(500_000)), bob(), String::new())
+        .unwrap();
+
+    assert_eq!(withdrawn.withdrawn_amount, U128(195_000));
+    assert_eq!(withdrawn.fee, U128(1_969));
+
+    let jar = context.contract().get_jar_internal(&alice, reference_jar.id);
+
+    assert_eq!(
+        jar.principal,
+        reference_jar.principal - 500_000 + 300_000 + 5_000 - 1_969
+    );
+    assert!(!jar.is_pending_withdraw);
+}
+
+#[test]
+fn withdraw_and_call_with_failed_fee_transfer() {
+    let product = Product::new().with_withdrawal_fee(WithdrawalFee::Fix(10));
+    let (alice, reference_jar, context) = prepare_jar(&product);
+
+    let mut contract = context.contract();
+
+    let jar = contract.get_jar_internal(&alice, reference_jar.id).clone();
+
+    let locked_jar = contract.get_jar_mut_internal(&alice, reference_jar.id);
+    locked_jar.principal -= 500_000;
+    locked_jar.lock(LockOperation::Withdraw, 0);
+
+    let withdrawn = contract.after_withdraw_and_call_fee_internal(
+        alice.clone(),
+        JarWithdraw {
+            jar: jar.clone(),
+            should_be_closed: false,
+            amount: 500_000,
+            fee: Some(10),
+        },
+        bob(),
+        200_000,
+        Some(Fee::new("fee".parse().unwrap(), 10)),
+        false,
+    );
+
+    assert_eq!(withdrawn.withdrawn_amount, U128(200_000));
+    assert_eq!(withdrawn.fee, U128(0));
+
+    let jar_after = contract.get_jar_internal(&alice, reference_jar.id);
+
+    assert_eq!(jar_after.principal, jar.principal - 200_000);
+    assert!(!jar_after.is_pending_withdraw);
+}
+
+#[test]
+fn withdraw_and_call_with_full_refund() {
+    let fee = 10;
+    let product = Product::new().with_withdrawal_fee(WithdrawalFee::Fix(fee));
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+    context.switch_account(&alice);
+
+    context.contract().claim_total(None);
+
+    set_test_transfer_call_refund(reference_jar.principal - fee);
+
+    let withdrawn = context
+        .contract()
+        .withdraw_and_call(U32(reference_jar.id), None, bob(), String::new())
+        .unwrap();
+
+    assert_eq!(withdrawn.withdrawn_amount.0, 0);
+    assert_eq!(withdrawn.fee.0, 0);
+
+    let jar = context.contract().get_jar_internal(&alice, reference_jar.id);
+
+    assert_eq!(jar.principal, reference_jar.principal);
+    assert!(!jar.is_pending_withdraw);
+}
+
+#[test]
+fn test_failed_withdraw_and_call() {
+    set_test_future_success(false);
+
+    let product = Product::new();
+    let (alice, reference_jar, mut context) = prepare_jar(&product);
+
+    context.set_block_timestamp_in_ms(product.get_lockup_term().unwrap() + 1);
+    context.switch_account(&alice);
+
+    let jar_before_withdrawal = context.contract().get_jar(alice.clone(), U32(reference_jar.id));
+
+    let withdrawn = context
+        .contract()
+        .withdraw_and_call(U32(reference_jar.id), None, bob(), String::new())
+        .unwrap();
+
+    assert_eq!(withdrawn.withdrawn_amount.0, 0);
+    assert_eq!(withdrawn.fee.0, 0);
+
+    let jar_after_withdrawal = context.contract().get_jar(alice, U32(reference_jar.id));
+
+    assert_eq!(jar_before_withdrawal, jar_after_withdrawal);
+}
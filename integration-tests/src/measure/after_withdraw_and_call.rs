@@ -0,0 +1,106 @@
+use anyhow::Result;
+use near_workspaces::types::Gas;
+use nitka::{misc::ToNear, near_sdk::serde_json::json};
+use sweat_jar_model::{api::WithdrawApiIntegration, U32};
+
+use crate::{
+    context::{prepare_contract, IntegrationContext},
+    jar_contract_extensions::JarContractExtensions,
+    measure::{measure::scoped_command_measure, utils::generate_permutations},
+    product::RegisterProductCommand,
+};
+
+/// Measures `after_withdraw_and_call` callback alone. The jar contract itself is used as the receiver:
+/// a stake message uses all the tokens and an empty message makes the receiver refund everything.
+#[ignore]
+#[tokio::test]
+#[mutants::skip]
+async fn measure_after_withdraw_and_call_test() -> Result<()> {
+    let result = scoped_command_measure(
+        generate_permutations(
+            &[
+                RegisterProductCommand::Locked10Minutes6Percents,
+                RegisterProductCommand::Locked10Minutes6PercentsWithPercentWithdrawFee,
+                RegisterProductCommand::Locked10Minutes6PercentsWithFixedWithdrawFee,
+            ],
+            &[false, true],
+        ),
+        measure_one_after_withdraw_and_call,
+    )
+    .await?;
+
+    let all_gas: Vec<_> = result.into_iter().map(|res| res.1).collect();
+
+    dbg!(&all_gas);
+
+    dbg!(all_gas.iter().max());
+    dbg!(all_gas.iter().min());
+
+    Ok(())
+}
+
+#[ignore]
+#[tokio::test]
+#[mutants::skip]
+async fn one_after_withdraw_and_call_with_full_refund() -> Result<()> {
+    let gas = measure_one_after_withdraw_and_call((
+        RegisterProductCommand::Locked10Minutes6PercentsWithPercentWithdrawFee,
+        true,
+    ))
+    .await?;
+
+    dbg!(&gas);
+
+    Ok(())
+}
+
+#[mutants::skip]
+async fn measure_one_after_withdraw_and_call(data: (RegisterProductCommand, bool)) -> Result<Gas> {
+    let (product, full_refund) = data;
+
+    let mut context = prepare_contract(None, [product]).await?;
+
+    let alice = context.alice().await?;
+
+    context
+        .sweat_jar()
+        .create_jar(&alice, product.id(), 100_000, &context.ft_contract())
+        .await?;
+
+    context.fast_forward_hours(1).await?;
+
+    let msg = if full_refund {
+        String::new()
+    } else {
+        json!({
+            "type": "stake",
+            "data": {
+                "ticket": {
+                    "product_id": product.id(),
+                    "valid_until": "0",
+                }
+            }
+        })
+        .to_string()
+    };
+
+    let jar_account = context.sweat_jar().contract.as_account().to_near();
+
+    let result = context
+        .sweat_jar()
+        .withdraw_and_call(U32(0), None, jar_account.clone(), msg)
+        .with_user(&alice)
+        .result()
+        .await?;
+
+    // The callback is the last receipt executed by the jar contract. When a fee is charged, it is
+    // `after_withdraw_and_call_fee`, which does the settlement `after_withdraw_and_call` does without a fee.
+    let callback = result
+        .receipt_outcomes()
+        .iter()
+        .rev()
+        .find(|outcome| outcome.executor_id.as_str() == jar_account.as_str())
+        .expect("Callback receipt is not found");
+
+    Ok(callback.gas_burnt)
+}
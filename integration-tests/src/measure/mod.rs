@@ -1,8 +1,10 @@
 mod after_claim;
 mod after_withdraw;
+mod after_withdraw_and_call;
 mod batch_penalty;
 mod claim;
 pub(crate) mod measure;
+mod products;
 pub(crate) mod random_element;
 mod register_product;
 mod restake;
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use near_workspaces::types::Gas;
+use sweat_jar_model::api::ClaimApiIntegration;
+
+use crate::{
+    context::{prepare_contract, IntegrationContext},
+    measure::{measure::scoped_command_measure, utils::add_jar},
+    product::RegisterProductCommand,
+};
+
+const PRODUCTS: [RegisterProductCommand; 4] = [
+    RegisterProductCommand::Locked12Months12Percents,
+    RegisterProductCommand::Locked6Months6Percents,
+    RegisterProductCommand::Flexible6Months6Percents,
+    RegisterProductCommand::Locked10Minutes6Percents,
+];
+
+/// Measures how `claim_total` cost depends on the number of distinct products
+/// the account's jars refer to. Products are read through the cached `IterableMap`,
+/// so repeated reads of the same product should not add storage reads.
+#[ignore]
+#[tokio::test]
+#[mutants::skip]
+async fn measure_claim_with_products_test() -> Result<()> {
+    let measured = scoped_command_measure(1..=PRODUCTS.len(), measure_claim_with_products).await?;
+
+    dbg!(&measured);
+
+    Ok(())
+}
+
+#[mutants::skip]
+async fn measure_claim_with_products(products_count: usize) -> Result<Gas> {
+    let products = &PRODUCTS[..products_count];
+
+    let mut context = prepare_contract(None, products.iter().copied()).await?;
+
+    let alice = context.alice().await?;
+
+    for product in products {
+        add_jar(&context, &alice, *product, 100_000).await?;
+        add_jar(&context, &alice, *product, 100_000).await?;
+    }
+
+    context.fast_forward_hours(2).await?;
+
+    Ok(context
+        .sweat_jar()
+        .claim_total(None)
+        .with_user(&alice)
+        .result()
+        .await?
+        .total_gas_burnt)
+}
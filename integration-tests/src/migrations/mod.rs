@@ -1,3 +1,4 @@
 mod defi;
 pub mod helpers;
+mod reconciliation;
 mod v2;
@@ -0,0 +1,158 @@
+use anyhow::ensure;
+use near_workspaces::Account;
+use nitka::{json, misc::ToNear, near_sdk::serde_json::Value};
+use sweat_jar_model::{
+    api::{JarApiIntegration, MigrationToV2Integration, UnbondApiIntegration},
+    ProductId, TokenAmount,
+};
+
+use crate::context::{Context, IntegrationContext, SWEAT_JAR_V2};
+
+/// A legacy jar as it's expected to be found in `sweat_jar_v2` contract after migration.
+#[derive(Debug)]
+pub(crate) struct LegacyJar {
+    pub id: u32,
+    pub product_id: ProductId,
+    /// Unbonding tokens are migrated as principal.
+    pub principal: TokenAmount,
+}
+
+/// Captures legacy jars of the account. Must be called before the account is migrated.
+pub(crate) async fn snapshot_legacy_jars(context: &Context, account: &Account) -> anyhow::Result<Vec<LegacyJar>> {
+    let unbonds = context.sweat_jar().get_pending_unbonds(account.to_near()).await?;
+
+    let jars = context
+        .sweat_jar()
+        .get_jars_for_account(account.to_near())
+        .await?
+        .into_iter()
+        .map(|jar| {
+            let unbonding = unbonds
+                .iter()
+                .filter(|unbond| unbond.jar_id == jar.id)
+                .map(|unbond| unbond.amount.0)
+                .sum::<TokenAmount>();
+
+            LegacyJar {
+                id: jar.id.0,
+                product_id: jar.product_id,
+                principal: jar.principal.0 + unbonding,
+            }
+        })
+        .collect();
+
+    Ok(jars)
+}
+
+/// Cross-checks the migration receipt of the account against its state in `sweat_jar_v2` contract.
+/// Besides totals, every legacy jar must be mapped to a jar of the same product and principal.
+pub(crate) async fn reconcile_migration_receipt(
+    context: &Context,
+    account: &Account,
+    legacy_jars: &[LegacyJar],
+) -> anyhow::Result<()> {
+    let receipt = context
+        .sweat_jar()
+        .get_migration_receipt(account.to_near())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Migration receipt for {} is missing", account.id()))?;
+
+    let v2_contract = context.contracts.get(SWEAT_JAR_V2).unwrap();
+
+    ensure!(
+        receipt.target_contract == v2_contract.as_account().to_near(),
+        "Receipt targets {}, expected {}",
+        receipt.target_contract,
+        v2_contract.as_account().id()
+    );
+
+    let principal: Value = v2_contract
+        .call("get_total_principal")
+        .args_json(json!({ "account_id": account.to_near() }))
+        .view()
+        .await?
+        .json()?;
+    let principal: u128 = principal["total"].as_str().unwrap_or_default().parse()?;
+
+    ensure!(
+        principal == receipt.principal.0,
+        "Principal in v2 contract is {principal}, receipt has {}",
+        receipt.principal.0
+    );
+
+    let interest: Value = v2_contract
+        .call("get_total_interest")
+        .args_json(json!({ "account_id": account.to_near() }))
+        .view()
+        .await?
+        .json()?;
+    let interest: u128 = interest["amount"]["total"].as_str().unwrap_or_default().parse()?;
+
+    ensure!(
+        interest >= receipt.interest.0,
+        "Interest in v2 contract is {interest}, receipt carried {}",
+        receipt.interest.0
+    );
+
+    let mut receipt_jar_ids: Vec<_> = receipt.jar_ids.iter().map(|id| id.0).collect();
+    let mut legacy_jar_ids: Vec<_> = legacy_jars.iter().map(|jar| jar.id).collect();
+    receipt_jar_ids.sort_unstable();
+    legacy_jar_ids.sort_unstable();
+
+    ensure!(
+        receipt_jar_ids == legacy_jar_ids,
+        "Receipt has jars {receipt_jar_ids:?}, legacy jars are {legacy_jar_ids:?}"
+    );
+
+    let v2_jars: Value = v2_contract
+        .call("get_jars_for_account")
+        .args_json(json!({ "account_id": account.to_near() }))
+        .view()
+        .await?
+        .json()?;
+
+    let mut unmatched = Vec::new();
+    collect_v2_jars(&v2_jars, &mut unmatched)?;
+
+    for jar in legacy_jars {
+        let position = unmatched
+            .iter()
+            .position(|(product_id, principal)| product_id == &jar.product_id && *principal == jar.principal)
+            .ok_or_else(|| anyhow::anyhow!("Legacy jar {jar:?} has no matching jar in v2 contract"))?;
+
+        unmatched.swap_remove(position);
+    }
+
+    ensure!(
+        unmatched.is_empty(),
+        "Jars in v2 contract don't originate from legacy jars: {unmatched:?}"
+    );
+
+    Ok(())
+}
+
+/// Collects `(product_id, principal)` of every jar view found in `get_jars_for_account` response of v2 contract.
+fn collect_v2_jars(value: &Value, jars: &mut Vec<(ProductId, TokenAmount)>) -> anyhow::Result<()> {
+    match value {
+        Value::Object(object) => {
+            if let (Some(Value::String(product_id)), Some(Value::String(principal))) =
+                (object.get("product_id"), object.get("principal"))
+            {
+                jars.push((product_id.clone(), principal.parse()?));
+                return Ok(());
+            }
+
+            for value in object.values() {
+                collect_v2_jars(value, jars)?;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_v2_jars(value, jars)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -3,6 +3,7 @@ use sweat_jar_model::api::{IntegrationTestMethodsIntegration, MigrationToV2Integ
 
 use crate::{
     context::{prepare_contract, IntegrationContext, SWEAT_JAR_V2},
+    migrations::reconciliation::{reconcile_migration_receipt, snapshot_legacy_jars},
     product::RegisterProductCommand,
 };
 
@@ -79,18 +80,32 @@ async fn migrate_to_v2() -> anyhow::Result<()> {
         .await?;
     println!("Initialization is successful: {:?}", result.is_success());
 
-    let result = context
-        .sweat_jar()
-        .migrate_products()
-        .with_user(&manager)
-        .result()
-        .await;
-    assert!(result.is_ok(), "🚨 Products migration failed: {:?}", result);
+    // Products are sent in chunks to fit into the gas limit
+    for chunk in original_products.chunks(2) {
+        let product_ids = chunk.iter().map(RegisterProductCommand::id).collect();
+
+        let result = context
+            .sweat_jar()
+            .migrate_products(Some(product_ids))
+            .with_user(&manager)
+            .result()
+            .await;
+        assert!(result.is_ok(), "🚨 Products migration failed: {:?}", result);
+    }
+
+    let migration = context.sweat_jar().get_products_migration().await?;
+    assert!(migration.pending.is_empty());
+    assert_eq!(original_products.len(), migration.migrated.len());
 
     let products: Vec<Value> = v2_contract.call("get_products").view().await?.json()?;
     assert_eq!(original_products.len(), products.len());
 
+    let legacy_jars = snapshot_legacy_jars(&context, &alice).await?;
+
     context.sweat_jar().migrate_account().with_user(&alice).await?;
 
+    println!("👷🏽 Reconcile migration receipt");
+    reconcile_migration_receipt(&context, &alice, &legacy_jars).await?;
+
     Ok(())
 }
@@ -68,6 +68,9 @@ fn _get_products() -> Vec<RegisterProductCommand> {
             public_key: Some(pk.into()),
             is_enabled,
             score_cap: 0,
+            streak: None,
+            unbonding_period: None,
+            fee_split: None,
         })
     }
 
@@ -90,6 +93,9 @@ async fn register_test_product(manager: &Account, jar: &SweatJarContract<'_>) ->
         public_key: None,
         is_enabled: true,
         score_cap: 20_000,
+        streak: None,
+        unbonding_period: None,
+        fee_split: None,
     })
     .with_user(manager)
     .await?;
@@ -103,3 +103,68 @@ async fn withdraw_all() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[mutants::skip]
+async fn withdraw_all_with_fee() -> Result<()> {
+    const PRINCIPAL: u128 = 1_000_000;
+    const FIXED_FEE: u128 = 1_000;
+    const PERCENT_FEE: u128 = 10_000;
+
+    println!("👷🏽 Run test for withdraw all with fee");
+
+    set_integration_logs_enabled(false);
+
+    let fixed_fee_product = RegisterProductCommand::Locked10Minutes6PercentsWithFixedWithdrawFee;
+    let percent_fee_product = RegisterProductCommand::Locked10Minutes6PercentsWithPercentWithdrawFee;
+
+    let mut context = prepare_contract(None, [fixed_fee_product, percent_fee_product]).await?;
+
+    let alice = context.alice().await?;
+    let fee_account = context.fee().await?;
+
+    context
+        .sweat_jar()
+        .create_jar(&alice, fixed_fee_product.id(), PRINCIPAL, &context.ft_contract())
+        .await?;
+    context
+        .sweat_jar()
+        .create_jar(&alice, percent_fee_product.id(), PRINCIPAL, &context.ft_contract())
+        .await?;
+
+    context.fast_forward_hours(1).await?;
+
+    context.sweat_jar().claim_total(None).with_user(&alice).await?;
+
+    let alice_balance = context.ft_contract().ft_balance_of(alice.to_near()).await?;
+    let fee_balance = context.ft_contract().ft_balance_of(fee_account.to_near()).await?;
+
+    let withdrawn = context.sweat_jar().withdraw_all(None).with_user(&alice).await?;
+
+    assert_eq!(
+        withdrawn.jars.iter().map(|j| j.fee.0).collect::<Vec<_>>(),
+        vec![FIXED_FEE, PERCENT_FEE]
+    );
+    assert_eq!(
+        withdrawn.jars.iter().map(|j| j.withdrawn_amount.0).collect::<Vec<_>>(),
+        vec![PRINCIPAL - FIXED_FEE, PRINCIPAL - PERCENT_FEE]
+    );
+    assert_eq!(withdrawn.total_amount.0, PRINCIPAL * 2 - FIXED_FEE - PERCENT_FEE);
+
+    let alice_balance_after = context.ft_contract().ft_balance_of(alice.to_near()).await?;
+    let fee_balance_after = context.ft_contract().ft_balance_of(fee_account.to_near()).await?;
+
+    assert_eq!(alice_balance_after.0 - alice_balance.0, withdrawn.total_amount.0);
+    assert_eq!(
+        fee_balance_after.0 - fee_balance.0,
+        withdrawn.jars.iter().map(|j| j.fee.0).sum::<u128>()
+    );
+
+    assert!(context
+        .sweat_jar()
+        .get_jars_for_account(alice.to_near())
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -4,10 +4,10 @@ use near_sdk::{env, env::panic_str, near};
 
 use crate::{
     jar::{Deposit, Jar},
-    ProductId, Score, Timestamp, Timezone, TokenAmount, UTC,
+    ProductId, Timestamp, Timezone, TokenAmount, UTC,
 };
 
-#[near]
+#[near(serializers=[borsh, json])]
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct AccountV1 {
     pub nonce: u32,
@@ -18,15 +18,16 @@ pub struct AccountV1 {
 
 const DAYS_STORED: usize = 2;
 
+/// Scores are stored as `u16` here to match the account layout expected by v2 contract.
 #[near(serializers=[borsh, json])]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct AccountScore {
     pub updated: UTC,
     pub timezone: Timezone,
     /// Scores buffer used for interest calculation. Can be invalidated on claim.
-    pub scores: [Score; DAYS_STORED],
+    pub scores: [u16; DAYS_STORED],
     /// Score history values used for displaying it in application. Will not be invalidated during claim.
-    pub scores_history: [Score; DAYS_STORED],
+    pub scores_history: [u16; DAYS_STORED],
 }
 
 impl Default for AccountScore {
@@ -4,10 +4,16 @@ use nitka_proc::make_integration_version;
 
 use crate::{
     claimed_amount_view::ClaimedAmountView,
-    jar::{AggregatedInterestView, AggregatedTokenAmountView, JarIdView, JarView},
+    export::AccountExportView,
+    jar::{
+        AggregatedInterestView, AggregatedTokenAmountView, ClaimOperatorView, ClaimTarget, ClosedJarView, JarIdView,
+        JarTicket, JarView, StuckLockView, UnbondingView,
+    },
+    migration::{AccountMigrationPreview, LegacyAccountsMigrationView, MigrationReceiptView, ProductsMigrationView},
     product::{ProductView, RegisterProductCommand},
+    upgrade::StagedUpgradeView,
     withdraw::{BulkWithdrawView, WithdrawView},
-    ProductId, Score, UTC,
+    ProductId, Score, ScoreProjectionView, UTC,
 };
 
 #[cfg(feature = "integration-test")]
@@ -42,6 +48,72 @@ pub trait ClaimApi {
     /// and probably a map containing amount of tokens claimed from each Jar. If the total available
     /// interest across all jars is zero, the returned value will also be zero and the detailed map will be empty (if requested).
     fn claim_total(&mut self, detailed: Option<bool>) -> ::near_sdk::PromiseOrValue<ClaimedAmountView>;
+
+    /// Claims available interest the same way as `claim_total`, but instead of transferring it to the user
+    /// puts it into a jar: either tops up an existing jar or creates a new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - A `ClaimTarget` specifying the jar which receives the interest.
+    ///
+    /// # Returns
+    ///
+    /// A `JarView` of the jar which received the interest.
+    ///
+    /// # Panics
+    ///
+    /// - If there is no interest to claim.
+    /// - If the target jar doesn't allow top-ups or the interest doesn't fit its product cap.
+    /// - If the ticket for a new jar is not valid, the same way as in `ft_on_transfer`.
+    /// - If the product for a new jar requires a signature.
+    fn claim_to_jar(&mut self, target: ClaimTarget) -> JarView;
+
+    /// Claims available interest on behalf of `account_id` the same way as `claim_total`.
+    /// Can be called only by a claim operator approved by the account. Claimed tokens are transferred
+    /// to the account itself or to a receiver approved together with the operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The ID of the account whose jars are claimed.
+    /// * `detailed` - The same as in `claim_total`.
+    ///
+    /// # Panics
+    ///
+    /// - If the caller is not a claim operator of the account.
+    fn claim_for(
+        &mut self,
+        account_id: ::near_sdk::AccountId,
+        detailed: Option<bool>,
+    ) -> ::near_sdk::PromiseOrValue<ClaimedAmountView>;
+
+    /// Allows `operator_id` to claim interest on behalf of the calling account.
+    /// Approving an already approved operator replaces its receiver.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator_id` - The ID of the account allowed to call `claim_for`.
+    /// * `receiver_id` - An optional ID of the account receiving tokens claimed by the operator.
+    ///                   If it's not set, the tokens are transferred to the calling account.
+    ///
+    /// # Panics
+    ///
+    /// - If the calling account doesn't have jars.
+    /// - If the calling account already has the maximum number of claim operators.
+    fn approve_claim_operator(
+        &mut self,
+        operator_id: ::near_sdk::AccountId,
+        receiver_id: Option<::near_sdk::AccountId>,
+    );
+
+    /// Revokes the permission of `operator_id` to claim interest on behalf of the calling account.
+    ///
+    /// # Panics
+    ///
+    /// - If `operator_id` is not a claim operator of the calling account.
+    fn revoke_claim_operator(&mut self, operator_id: ::near_sdk::AccountId);
+
+    /// Returns claim operators approved by `account_id`.
+    fn get_claim_operators(&self, account_id: ::near_sdk::AccountId) -> Vec<ClaimOperatorView>;
 }
 
 /// The `JarApi` trait defines methods for managing deposit jars and their associated data within the smart contract.
@@ -69,6 +141,18 @@ pub trait JarApi {
     /// A `Vec<JarView>` containing details about all deposit jars belonging to the specified account.
     fn get_jars_for_account(&self, account_id: ::near_sdk::AccountId) -> Vec<JarView>;
 
+    /// Exports a snapshot of the account state for off-chain audits.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The `AccountId` of the account to export.
+    ///
+    /// # Returns
+    ///
+    /// An `AccountExportView` with every jar of the account, its interest and maturity, and the score state.
+    /// The format is versioned and stable, so snapshots taken at different blocks can be diffed.
+    fn export_account(&self, account_id: ::near_sdk::AccountId) -> AccountExportView;
+
     /// Retrieves the total principal amount across all deposit jars for a provided account.
     ///
     /// # Arguments
@@ -141,7 +225,111 @@ pub trait JarApi {
     /// Restakes all jars for user, or only specified list of jars if `jars` argument is `Some`
     fn restake_all(&mut self, jars: Option<Vec<JarIdView>>) -> Vec<JarView>;
 
+    /// Moves the principal of a mature jar into a new jar of another product, e.g. a successor
+    /// of a product which is being sunset. The original product doesn't need to support restaking.
+    ///
+    /// # Arguments
+    ///
+    /// * `jar_id` - The ID of the mature deposit jar.
+    /// * `ticket` - A `JarTicket` for the target product. It's verified the same way as in `ft_on_transfer`.
+    /// * `signature` - An optional signature of the ticket. It's required if the target product has a public key.
+    ///
+    /// # Returns
+    ///
+    /// A `JarView` containing details about the new jar.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic under the following conditions:
+    /// - If the function is called by an account other than the owner of the original jar.
+    /// - If the original jar is not yet mature or is empty.
+    /// - If the target product is disabled or its cap doesn't allow the principal of the jar.
+    /// - If the signature is missing or invalid for the target product with a public key.
+    fn restake_into(
+        &mut self,
+        jar_id: JarIdView,
+        ticket: JarTicket,
+        signature: Option<::near_sdk::json_types::Base64VecU8>,
+    ) -> JarView;
+
+    /// Turns automatic restaking of a Fixed jar on or off. An auto-renewed jar rolls into a new term
+    /// at maturity, so its interest keeps accruing without a gap. Turning it off makes the jar
+    /// mature at the end of its current term.
+    ///
+    /// # Arguments
+    ///
+    /// * `jar_id` - The ID of the deposit jar.
+    /// * `auto_renew` - Whether the jar should roll into a new term at maturity.
+    ///
+    /// # Returns
+    ///
+    /// A `JarView` of the updated jar.
+    ///
+    /// # Panics
+    ///
+    /// This function may panic under the following conditions:
+    /// - If the product of the jar does not support restaking.
+    /// - If the function is called by an account other than the owner of the jar.
+    /// - If auto-renewal is turned on for a jar that is already mature.
+    fn set_auto_renew(&mut self, jar_id: JarIdView, auto_renew: bool) -> JarView;
+
     fn unlock_jars_for_account(&mut self, account_id: ::near_sdk::AccountId);
+
+    /// Lists jars of the account which stay locked longer than the callback of the pending operation could take.
+    /// It happens if the callback fails, e.g. because it runs out of gas.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The ID of the account for which stuck locks are being retrieved.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `StuckLockView` describing the operation which locked each jar and when it happened.
+    /// Jars locked before operations were recorded have neither of them.
+    fn get_stuck_locks(&self, account_id: ::near_sdk::AccountId) -> Vec<StuckLockView>;
+
+    /// Releases stuck locks of the caller's jars, so they can be claimed and withdrawn again
+    /// without a call from the manager. Jars with pending operations which aren't stuck yet stay locked.
+    ///
+    /// # Returns
+    ///
+    /// A vector of IDs of the released jars.
+    fn release_stuck_locks(&mut self) -> Vec<JarIdView>;
+
+    /// Turns the archive of closed jars on or off for the caller. When the archive is on, jars deleted
+    /// from the account are recorded, and the oldest records are dropped once the archive is full.
+    /// Turning the archive off removes all records.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether closed jars of the caller should be recorded.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the caller doesn't have jars.
+    fn set_jar_archive(&mut self, enabled: bool);
+
+    /// Retrieves jars closed by `account_id`, starting from the oldest one.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `ClosedJarView`. It's empty if the archive is off.
+    fn get_closed_jars(&self, account_id: ::near_sdk::AccountId) -> Vec<ClosedJarView>;
+
+    /// Removes records of the caller's closed jars to free storage. The archive stays on.
+    ///
+    /// # Arguments
+    ///
+    /// * `closed_before` - Only jars closed before this timestamp are removed. All records are removed if it's `None`.
+    ///
+    /// # Returns
+    ///
+    /// The number of removed records.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the archive of the caller is off.
+    fn prune_closed_jars(&mut self, closed_before: Option<::near_sdk::json_types::U64>) -> u32;
 }
 
 #[make_integration_version]
@@ -150,25 +338,158 @@ pub trait MigrationToClaimRemainder {
 }
 
 #[make_integration_version]
-pub trait MigratonToNearSdk5 {
-    fn migrate_state_to_near_sdk_5() -> Self;
+pub trait LegacyAccountsMigrationApi {
+    /// Sets the number of accounts left in legacy storages. It's known only off-chain,
+    /// so the manager provides it before starting the batched migration.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If called by an account other than the manager.
+    /// - If the migration is already complete.
+    fn set_legacy_accounts_count(&mut self, count: u32);
+
+    /// Moves provided accounts out of legacy storages.
+    /// Accounts which are already migrated are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `accounts` - A batch of accounts to migrate.
+    ///
+    /// # Returns
+    ///
+    /// A `LegacyAccountsMigrationView` with the migration progress.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If called by an account other than the manager.
+    /// - If the migration is already complete.
+    fn migrate_legacy_accounts(&mut self, accounts: Vec<::near_sdk::AccountId>) -> LegacyAccountsMigrationView;
+
+    /// Marks the migration as complete and closes the batched migration.
+    /// Accounts left in legacy storages because of a wrong count are still readable and migrated on access.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If called by an account other than the manager.
+    /// - If the number of accounts in legacy storages wasn't set with `set_legacy_accounts_count`.
+    /// - If there are accounts left in legacy storages.
+    fn complete_legacy_accounts_migration(&mut self);
+
+    fn get_legacy_accounts_migration(&self) -> LegacyAccountsMigrationView;
 }
 
 #[make_integration_version]
-pub trait MigrationToStepJars {
-    fn migrate_state_to_step_jars() -> Self;
+pub trait StateMigration {
+    /// Brings the contract state to the current layout by running all migration steps
+    /// registered after the state version recorded in the contract storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `legacy_version` - The version of the state layout. It's used only if the state was deployed
+    ///   before versions were recorded.
+    /// * `new_version_account_id` - The v2 contract accounts are migrated to. It's required only
+    ///   to migrate the state of version 0, which was deployed before the migration to v2 was configured.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If the state is already of the current version.
+    /// - If the state version is neither recorded nor provided, or it is unknown.
+    /// - If the state is of version 0 and `new_version_account_id` isn't provided.
+    fn migrate_state(legacy_version: Option<u32>, new_version_account_id: Option<::near_sdk::AccountId>) -> Self;
+}
+
+/// The `UpgradeApi` trait defines a two-phase upgrade of the contract code.
+/// The staged upgrade is executed with `execute_upgrade` method, which receives the wasm as raw input.
+#[make_integration_version]
+pub trait UpgradeApi {
+    /// Stages an upgrade, so users can verify the code before it's deployed.
+    ///
+    /// # Arguments
+    ///
+    /// * `code_hash` - Hex encoded sha256 of the wasm to deploy.
+    /// * `activate_at` - The earliest timestamp when the upgrade can be executed.
+    /// * `migrate` - Whether `migrate_state` must be called right after the code is deployed.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If called by an account other than the manager.
+    /// - If another upgrade is already staged.
+    /// - If `code_hash` is not a hex encoded sha256.
+    /// - If `activate_at` is earlier than the minimal upgrade delay.
+    fn stage_upgrade(&mut self, code_hash: String, activate_at: ::near_sdk::json_types::U64, migrate: bool);
+
+    /// Cancels the staged upgrade.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If called by an account other than the manager.
+    /// - If there is no staged upgrade.
+    fn cancel_upgrade(&mut self);
+
+    fn get_staged_upgrade(&self) -> Option<StagedUpgradeView>;
 }
 
 #[make_integration_version]
 pub trait MigrationToV2 {
-    fn migrate_state_to_v2_ready(new_version_account_id: ::near_sdk::AccountId) -> Self;
     fn migrate_account(&mut self) -> ::near_sdk::PromiseOrValue<(::near_sdk::AccountId, bool)>;
-    fn migrate_products(&mut self) -> ::near_sdk::PromiseOrValue<()>;
+
+    /// Sends a chunk of products to v2 contract. Products are recorded as migrated once v2 contract accepts them.
+    /// Until v2 contract responds, the sent products can't be sent again.
+    ///
+    /// # Arguments
+    ///
+    /// * `product_ids` - IDs of products to send. If it's not provided, all pending products are sent,
+    ///   so a large catalogue should be split into several calls to fit into the gas limit.
+    ///
+    /// # Returns
+    ///
+    /// A `PromiseOrValue<Vec<ProductId>>` with IDs of products acknowledged by v2 contract.
+    /// It's empty if v2 contract doesn't accept the products.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic under the following conditions:
+    /// - If called by an account other than the manager.
+    /// - If a product doesn't exist, is already migrated or is being migrated.
+    /// - If there are no products to send.
+    fn migrate_products(&mut self, product_ids: Option<Vec<ProductId>>) -> ::near_sdk::PromiseOrValue<Vec<ProductId>>;
+
+    fn get_products_migration(&self) -> ProductsMigrationView;
+
+    /// Performs a dry run of `migrate_account` without changing the state.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The ID of the account to preview the migration for.
+    ///
+    /// # Returns
+    ///
+    /// An `AccountMigrationPreview` with the account which would be sent to v2 contract,
+    /// transferred principal, merged interest and conditions preventing the migration.
+    fn preview_account_migration(&self, account_id: ::near_sdk::AccountId) -> AccountMigrationPreview;
+
+    /// Retrieves the receipt of the account migrated to v2 contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The ID of the migrated account.
+    ///
+    /// # Returns
+    ///
+    /// A `MigrationReceiptView` with the principal, carried interest and merged jars,
+    /// or `None` if the account wasn't migrated.
+    fn get_migration_receipt(&self, account_id: ::near_sdk::AccountId) -> Option<MigrationReceiptView>;
     fn is_account_locked(&self, account_id: ::near_sdk::AccountId) -> bool;
     fn unlock_account(&mut self, account_id: ::near_sdk::AccountId);
 }
 
-/// The `PenaltyApi` trait provides methods for applying or canceling penalties on premium jars within the smart contract.
+/// The `PenaltyApi` trait provides methods for applying or canceling penalties on premium and score based jars within the smart contract.
 #[make_integration_version]
 pub trait PenaltyApi {
     /// Sets the penalty status for a specified jar.
@@ -177,6 +498,9 @@ pub trait PenaltyApi {
     /// with products having Downgradable APY. When a user violates the terms of a premium product and a penalty is applied, the
     /// interest for the jar is calculated using a downgraded APY rate. If the terms are no longer violated, the penalty can be canceled.
     ///
+    /// For score based jars the interest accrued so far is settled when the penalty status changes, and while the penalty
+    /// is applied recorded scores don't bring any interest. There is no partial penalty for them.
+    ///
     /// # Arguments
     ///
     /// * `account_id` - The account of user which owns this jar.
@@ -185,7 +509,7 @@ pub trait PenaltyApi {
     ///
     /// # Panics
     ///
-    /// This method will panic if the jar's associated product has a constant APY rather than a downgradable APY.
+    /// This method will panic if the jar's associated product isn't score based and has a constant APY rather than a downgradable APY.
     fn set_penalty(&mut self, account_id: ::near_sdk::AccountId, jar_id: JarIdView, value: bool);
 
     /// Batched version of `set_penalty`
@@ -197,7 +521,7 @@ pub trait PenaltyApi {
     ///
     /// # Panics
     ///
-    /// This method will panic if the jar's associated product has a constant APY rather than a downgradable APY.
+    /// This method will panic if the jar's associated product isn't score based and has a constant APY rather than a downgradable APY.
     fn batch_set_penalty(&mut self, jars: Vec<(::near_sdk::AccountId, Vec<JarIdView>)>, value: bool);
 }
 
@@ -277,14 +601,92 @@ pub trait WithdrawApi {
     /// - If the caller is not the owner of the specified jar.
     /// - If the withdrawal amount exceeds the available balance in the jar.
     /// - If attempting to withdraw from a Fixed jar that is not yet mature.
+    ///
+    /// For products with unbonding period the tokens are not transferred immediately. They are moved to
+    /// unbonding and can be transferred with `finalize_unbond` after the period ends. In this case
+    /// the returned withdrawn amount and fee are 0. A jar can have only one pending unbond,
+    /// so the call panics if the previous one is neither finalized nor cancelled.
     fn withdraw(
         &mut self,
         jar_id: JarIdView,
         amount: Option<::near_sdk::json_types::U128>,
     ) -> ::near_sdk::PromiseOrValue<WithdrawView>;
 
-    /// Withdraws all jars for user, or only specified list of jars if `jars` argument is `Some`
+    /// Withdraws all jars for user, or only specified list of jars if `jars` argument is `Some`.
+    /// Jars of products with unbonding period are skipped.
     fn withdraw_all(&mut self, jars: Option<Vec<JarIdView>>) -> ::near_sdk::PromiseOrValue<BulkWithdrawView>;
+
+    /// Withdraws tokens from a deposit jar and forwards them to `receiver_id` with `ft_transfer_call`.
+    /// The jar stays locked until the transfer is resolved. The amount refunded by the receiver
+    /// is returned back to the jar, and the withdrawal fee is charged only on the part which wasn't refunded.
+    ///
+    /// # Arguments
+    ///
+    /// * `jar_id` - The ID of the deposit jar from which the withdrawal is being made.
+    /// * `amount` - An optional `U128` value indicating the amount of tokens to withdraw. If `None` is provided,
+    ///              the entire balance of the jar will be withdrawn.
+    /// * `receiver_id` - The account of a contract which receives the tokens.
+    /// * `msg` - The message passed to `ft_on_transfer` of the receiver.
+    ///
+    /// # Returns
+    ///
+    /// A `PromiseOrValue<WithdrawView>` which contains the amount of tokens used by the receiver and the fee,
+    /// if it's defined by the associated Product. If the transfer fails, both values are 0.
+    ///
+    /// # Panics
+    ///
+    /// This function panics under the same conditions as `withdraw`
+    /// and if the product of the jar has unbonding period.
+    fn withdraw_and_call(
+        &mut self,
+        jar_id: JarIdView,
+        amount: Option<::near_sdk::json_types::U128>,
+        receiver_id: ::near_sdk::AccountId,
+        msg: String,
+    ) -> ::near_sdk::PromiseOrValue<WithdrawView>;
+}
+
+#[make_integration_version]
+pub trait UnbondApi {
+    /// Returns tokens requested for withdrawal from jars of products with unbonding period.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The ID of the user account.
+    ///
+    /// # Returns
+    ///
+    /// A list of `UnbondingView` for every jar of the account with a pending unbond.
+    fn get_pending_unbonds(&self, account_id: ::near_sdk::AccountId) -> Vec<UnbondingView>;
+
+    /// Transfers unbonded tokens to the owner of the jar once the unbonding period is over.
+    ///
+    /// # Arguments
+    ///
+    /// * `jar_id` - The ID of the jar with a pending unbond.
+    ///
+    /// # Returns
+    ///
+    /// A `PromiseOrValue<WithdrawView>` with the transferred amount and fee, if it's defined by the associated Product.
+    /// If the transfer fails, the tokens stay in unbonding and both values are 0.
+    /// If the jar has neither principal nor interest left after the transfer, it is closed.
+    ///
+    /// # Panics
+    ///
+    /// - If the jar doesn't have a pending unbond.
+    /// - If the unbonding period is not over yet.
+    fn finalize_unbond(&mut self, jar_id: JarIdView) -> ::near_sdk::PromiseOrValue<WithdrawView>;
+
+    /// Returns unbonding tokens back to the principal of the jar, so they accrue interest again.
+    ///
+    /// # Arguments
+    ///
+    /// * `jar_id` - The ID of the jar with a pending unbond.
+    ///
+    /// # Panics
+    ///
+    /// - If the jar doesn't have a pending unbond.
+    fn cancel_unbond(&mut self, jar_id: JarIdView);
 }
 
 #[make_integration_version]
@@ -311,6 +713,22 @@ pub trait ScoreApi {
 
     /// Returns current active score interest if user has any step jars
     fn get_score_interest(&self, account_id: ::near_sdk::AccountId) -> Option<::near_sdk::json_types::U128>;
+
+    /// Returns the number of consecutive days on which user reached streak threshold if user has any step jars
+    fn get_streak(&self, account_id: ::near_sdk::AccountId) -> Option<u16>;
+
+    /// Returns projected earnings for each step jar of the account.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The ID of the account to project earnings for.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `ScoreProjectionView` with APY implied by today's and yesterday's score,
+    /// the interest to be credited on the next `record_score` call and the maximum daily interest
+    /// achievable within product's `score_cap`. Empty if the account has no step jars.
+    fn get_score_projection(&self, account_id: ::near_sdk::AccountId) -> Vec<ScoreProjectionView>;
 }
 
 #[cfg(feature = "integration-methods")]
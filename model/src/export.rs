@@ -0,0 +1,86 @@
+use near_sdk::{
+    json_types::{U128, U64},
+    near, AccountId,
+};
+
+use crate::{jar::JarIdView, ProductId, Score, Timezone, UTC};
+
+/// Version of `AccountExportView` format. It's increased on every change of the format,
+/// so snapshots taken at different blocks can be compared safely.
+pub const ACCOUNT_EXPORT_VERSION: u32 = 1;
+
+/// A fully materialized snapshot of an account for off-chain audits.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct AccountExportView {
+    pub version: u32,
+
+    pub account_id: AccountId,
+
+    /// Block at which the snapshot was taken.
+    pub block_height: U64,
+
+    /// Timestamp which accrued interest is calculated for.
+    pub timestamp: U64,
+
+    /// Jars of the account sorted by ID.
+    pub jars: Vec<JarExportView>,
+
+    /// Absent if the account has no step jars.
+    pub score: Option<ScoreExportView>,
+
+    pub total_principal: U128,
+
+    pub total_accrued_interest: U128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct JarExportView {
+    pub id: JarIdView,
+
+    pub product_id: ProductId,
+
+    /// Hex encoded sha256 of the product terms. It changes if the product is updated.
+    pub product_terms_hash: String,
+
+    pub created_at: U64,
+
+    pub principal: U128,
+
+    pub cached_interest: U128,
+
+    pub cache_updated_at: Option<U64>,
+
+    pub claim_remainder: U64,
+
+    pub claimed_balance: U128,
+
+    /// Interest available to claim at the moment of the snapshot, including the cached one.
+    pub accrued_interest: U128,
+
+    /// The end of the lockup term. For auto-renewed jars it's the end of the current term.
+    /// Absent for jars of Flexible products.
+    pub matures_at: Option<U64>,
+
+    pub is_pending_withdraw: bool,
+
+    pub is_penalty_applied: bool,
+
+    pub auto_renew: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct ScoreExportView {
+    pub updated: UTC,
+
+    pub timezone: Timezone,
+
+    /// Scores of yesterday and today which haven't been claimed yet.
+    pub scores: Vec<Score>,
+
+    pub scores_history: Vec<Score>,
+
+    pub streak: u16,
+}
@@ -5,12 +5,67 @@ use near_sdk::{
     near, AccountId, Timestamp,
 };
 
-use crate::{numbers::U32, ProductId, TokenAmount};
+use crate::{numbers::U32, ProductId, Timezone, TokenAmount};
 
 pub type JarId = u32;
 
 pub type JarIdView = U32;
 
+/// The `JarTicket` struct represents a request to create a deposit jar for a corresponding product.
+///
+/// The data from this `JarTicket` is later combined with additional data, including the contract
+/// account address, the recipient's account ID, the desired amount of tokens to deposit,
+/// and the ID of the last jar created for the recipient. The concatenation of this data
+/// forms a message that is then hashed using the SHA-256 algorithm. This resulting hash is used
+/// to verify the authenticity of the data against an Ed25519 signature provided in the `ft_transfer_call` data.
+#[derive(Clone, Debug)]
+#[near(serializers=[json])]
+pub struct JarTicket {
+    /// The unique identifier of the product for which the jar is intended to be created.
+    /// This `product_id` links the request to the specific terms and conditions of the product that will govern the behavior of the jar.
+    pub product_id: String,
+
+    /// Specifies the expiration date of the ticket. The expiration date is measured in milliseconds
+    /// since the Unix epoch. This property ensures that the request to create a jar is valid only
+    /// until the specified timestamp. After this timestamp, the ticket becomes
+    /// invalid and should not be accepted.
+    pub valid_until: U64,
+
+    /// An optional user timezone. Required for creating step jars.
+    pub timezone: Option<Timezone>,
+}
+
+/// The `ClaimTarget` enum defines a jar which receives interest claimed with `claim_to_jar`.
+#[derive(Clone, Debug)]
+#[near(serializers=[json])]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ClaimTarget {
+    /// Top up an existing jar of the account. The product of the jar must allow top-ups.
+    TopUp(JarIdView),
+
+    /// Create a new jar for the product specified in the ticket.
+    /// Products which require a signature are not supported, because the claimed amount
+    /// is not known in advance and can't be signed.
+    Stake(StakeTarget),
+}
+
+#[derive(Clone, Debug)]
+#[near(serializers=[json])]
+pub struct StakeTarget {
+    /// Data of the `JarTicket` required for validating the request and specifying the product.
+    pub ticket: JarTicket,
+}
+
+/// The `ClaimOperatorView` struct describes an account allowed to claim interest on behalf of another account.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct ClaimOperatorView {
+    pub operator_id: AccountId,
+
+    /// An account which receives claimed tokens instead of the owner.
+    pub receiver_id: Option<AccountId>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[near(serializers=[json])]
 pub struct JarView {
@@ -23,6 +78,53 @@ pub struct JarView {
     pub is_penalty_applied: bool,
     #[serde(default)]
     pub is_pending_withdraw: bool,
+    #[serde(default)]
+    pub auto_renew: bool,
+}
+
+/// Tokens requested for withdrawal from a product with unbonding period.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct UnbondingView {
+    pub jar_id: JarIdView,
+    pub amount: U128,
+    /// The timestamp after which the unbonding can be finalized.
+    pub unlock_at: U64,
+}
+
+/// An operation involving cross-contract calls which locked a jar until its callback is resolved.
+#[near(serializers=[borsh, json])]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum LockOperation {
+    Claim,
+    Withdraw,
+    WithdrawAll,
+    WithdrawAndCall,
+    FinalizeUnbond,
+}
+
+/// A jar which stays locked longer than the callback of its operation could take.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct StuckLockView {
+    pub jar_id: JarIdView,
+    /// Absent for jars locked before operations were recorded.
+    pub operation: Option<LockOperation>,
+    pub locked_at: Option<U64>,
+}
+
+/// A jar which was deleted from the account after its principal and interest had been withdrawn.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct ClosedJarView {
+    pub id: JarIdView,
+    pub product_id: ProductId,
+    /// The total amount of tokens deposited into the jar.
+    pub principal: U128,
+    /// The total amount of interest claimed from the jar.
+    pub claimed_balance: U128,
+    pub closed_at: U64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1,12 +1,15 @@
 pub mod account;
 pub mod api;
 pub mod claimed_amount_view;
+pub mod export;
 pub mod jar;
+pub mod migration;
 mod numbers;
 pub mod product;
 mod score;
 mod timezone;
 mod udecimal;
+pub mod upgrade;
 pub mod withdraw;
 
 pub use numbers::U32;
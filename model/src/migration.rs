@@ -0,0 +1,87 @@
+use near_sdk::{
+    json_types::{U128, U64},
+    near, AccountId,
+};
+
+use crate::{account::Account, jar::JarIdView, ProductId, Score};
+
+/// Progress of moving accounts out of legacy storages.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct LegacyAccountsMigrationView {
+    /// Number of accounts left in legacy storages. It's `None` until the manager sets it.
+    pub remaining: Option<u32>,
+
+    /// Number of accounts moved out of legacy storages.
+    pub migrated: u32,
+
+    /// Indicates whether the batched migration is closed.
+    pub is_complete: bool,
+}
+
+/// Result of a dry run of the account migration to v2 contract.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct AccountMigrationPreview {
+    /// The account in the layout of v2 contract which would be sent on migration.
+    /// Absent if it can't be built because some products don't exist.
+    pub account: Option<Account>,
+
+    /// Tokens which would be transferred to v2 contract, including unbonding ones.
+    pub principal: U128,
+
+    /// Accrued interest merged into interest caches of the v2 account jars.
+    pub interest: U128,
+
+    /// Conditions preventing the migration. The account can be migrated only if there are none.
+    pub blockers: Vec<MigrationBlocker>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum MigrationBlocker {
+    /// The account doesn't have jars to migrate.
+    NoJars,
+
+    /// The account migration is already in progress.
+    AccountIsMigrating,
+
+    /// Another operation on the jar is in progress.
+    JarIsLocked(JarIdView),
+
+    /// The product of some jars doesn't exist.
+    MissingProduct(ProductId),
+
+    /// The account has a score bigger than v2 contract can store.
+    UnsupportedScore(Score),
+}
+
+/// Evidence of an account migrated to v2 contract.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct MigrationReceiptView {
+    /// Tokens transferred to v2 contract.
+    pub principal: U128,
+
+    /// Accrued interest carried to v2 contract in jar caches.
+    pub interest: U128,
+
+    /// IDs of jars merged into v2 account.
+    pub jar_ids: Vec<JarIdView>,
+
+    pub target_contract: AccountId,
+
+    pub migrated_at: U64,
+}
+
+/// Progress of moving products to v2 contract.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct ProductsMigrationView {
+    /// Products acknowledged by v2 contract. They can't be sent again.
+    pub migrated: Vec<ProductId>,
+
+    /// Products which are not acknowledged yet.
+    pub pending: Vec<ProductId>,
+}
@@ -1,6 +1,6 @@
 use near_sdk::{
     json_types::{Base64VecU8, U128, U64},
-    near,
+    near, AccountId,
 };
 
 use crate::{ProductId, Score, MS_IN_YEAR};
@@ -48,6 +48,29 @@ pub enum TermsView {
 pub enum WithdrawalFeeView {
     Fix(U128),
     Percent(f32),
+    Schedule(Vec<FeeScheduleStepView>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct FeeScheduleStepView {
+    pub until: U64,
+    pub fee: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct StreakBonusView {
+    pub threshold: Score,
+    pub step: f32,
+    pub max_days: u16,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct FeeShareView {
+    pub beneficiary_id: AccountId,
+    pub share: f32,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -61,6 +84,12 @@ pub struct ProductView {
     pub is_enabled: bool,
     #[serde(default)]
     pub score_cap: Score,
+    #[serde(default)]
+    pub streak: Option<StreakBonusView>,
+    #[serde(default)]
+    pub unbonding_period: Option<U64>,
+    #[serde(default)]
+    pub fee_split: Option<Vec<FeeShareView>>,
 }
 
 #[near(serializers=[borsh, json])]
@@ -106,6 +135,40 @@ pub enum WithdrawalFeeDto {
     /// 2. Second element is exponent as an integer
     /// I.e. "0.12" becomes ("12", 2): 12 * 10^-2
     Percent(U128, u32),
+    /// Percent fee depending on the age of the jar. Steps must be sorted by `until`.
+    /// There is no fee after the last step.
+    Schedule(Vec<FeeScheduleStepDto>),
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(Clone, PartialEq, Debug)]
+pub struct FeeScheduleStepDto {
+    /// Age of the jar in milliseconds since its creation until which the fee is applied
+    pub until: U64,
+    /// Decimal representation of a percent, same as in `WithdrawalFeeDto::Percent`
+    pub fee: (U128, u32),
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(PartialEq, Clone, Debug)]
+pub struct StreakBonusDto {
+    /// Daily score required to continue a streak
+    pub threshold: Score,
+    /// Decimal representation of APY bonus for each day of a streak relative to score based APY:
+    /// I.e. ("5", 2) raises the APY by 5% of its value for every streak day
+    pub step: (U128, u32),
+    /// Number of streak days after which the bonus stops growing
+    pub max_days: u16,
+}
+
+#[near(serializers=[borsh, json])]
+#[derive(PartialEq, Clone, Debug)]
+pub struct FeeShareDto {
+    /// Account receiving this share of the withdrawal fee
+    pub beneficiary_id: AccountId,
+    /// Decimal representation of the share of the withdrawal fee:
+    /// I.e. ("7", 1) sends 70% of the fee to the beneficiary
+    pub share: (U128, u32),
 }
 
 #[near(serializers=[borsh, json])]
@@ -121,6 +184,16 @@ pub struct RegisterProductCommand {
     pub public_key: Option<Base64VecU8>,
     pub is_enabled: bool,
     pub score_cap: Score,
+    #[serde(default)]
+    pub streak: Option<StreakBonusDto>,
+    /// Time in milliseconds between a withdrawal request and the moment tokens can be transferred.
+    /// Applicable only for Flexible products.
+    #[serde(default)]
+    pub unbonding_period: Option<U64>,
+    /// Distribution of the withdrawal fee between beneficiaries. Shares must add up to 1.
+    /// If it's not set, the whole fee goes to the contract's fee account.
+    #[serde(default)]
+    pub fee_split: Option<Vec<FeeShareDto>>,
 }
 
 impl Default for RegisterProductCommand {
@@ -136,6 +209,9 @@ impl Default for RegisterProductCommand {
             public_key: None,
             is_enabled: true,
             score_cap: 0,
+            streak: None,
+            unbonding_period: None,
+            fee_split: None,
         }
     }
 }
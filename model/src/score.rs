@@ -1,6 +1,8 @@
-use crate::{UDecimal, UTC};
+use near_sdk::{json_types::U128, near};
 
-pub type Score = u16;
+use crate::{jar::JarIdView, UDecimal, UTC};
+
+pub type Score = u32;
 
 pub trait ToAPY {
     fn to_apy(self) -> UDecimal;
@@ -17,4 +19,28 @@ impl ToAPY for Score {
 pub struct ScoreRecord {
     pub score: Vec<Score>,
     pub updated: UTC,
+    /// Number of consecutive days of reaching streak threshold before the score was recorded.
+    pub streak: u16,
+}
+
+/// Projected earnings of a step jar based on the score recorded for its owner.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct ScoreProjectionView {
+    pub jar_id: JarIdView,
+
+    /// APY implied by the score walked today in user's timezone.
+    pub apy_today: f32,
+
+    /// APY implied by the score walked yesterday in user's timezone.
+    pub apy_yesterday: f32,
+
+    /// Maximum daily APY limited by product's `score_cap`.
+    pub max_apy: f32,
+
+    /// Interest that will be credited to the jar on the next `record_score` call.
+    pub pending_interest: U128,
+
+    /// Interest the jar receives for a day when the score reaches product's `score_cap`.
+    pub max_interest: U128,
 }
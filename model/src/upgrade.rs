@@ -0,0 +1,17 @@
+use near_sdk::{json_types::U64, near};
+
+/// A contract upgrade waiting for its timelock to expire.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct StagedUpgradeView {
+    /// Hex encoded sha256 of the wasm. It matches `openssl dgst -sha256` output of `scripts/check-contract-hash.sh`.
+    pub code_hash: String,
+
+    pub staged_at: U64,
+
+    /// The upgrade can't be executed before this timestamp.
+    pub activate_at: U64,
+
+    /// Whether `migrate_state` is called right after the code is deployed.
+    pub migrate: bool,
+}
@@ -11,6 +11,10 @@ pub struct WithdrawView {
 
     /// The possible fee that a user must pay for withdrawal, if it's defined by the associated Product.
     pub fee: U128,
+
+    /// Distribution of the fee between beneficiaries, if the associated Product splits it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fee_split: Vec<FeeTransfer>,
 }
 
 #[derive(Debug, Default)]
@@ -23,28 +27,61 @@ pub struct BulkWithdrawView {
 impl WithdrawView {
     #[must_use]
     pub fn new(amount: TokenAmount, fee: Option<Fee>) -> Self {
-        let (withdrawn_amount, fee) = fee.map_or((amount, 0), |fee| (amount - fee.amount, fee.amount));
+        let Some(fee) = fee else {
+            return Self {
+                withdrawn_amount: U128(amount),
+                fee: U128(0),
+                fee_split: vec![],
+            };
+        };
+
+        let fee_amount = fee.amount();
 
         Self {
-            withdrawn_amount: U128(withdrawn_amount),
-            fee: U128(fee),
+            withdrawn_amount: U128(amount - fee_amount),
+            fee: U128(fee_amount),
+            fee_split: if fee.is_split() { fee.transfers } else { vec![] },
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use near_sdk::json_types::U128;
+    use near_sdk::{json_types::U128, serde_json};
 
-    use crate::withdraw::{Fee, WithdrawView};
+    use crate::withdraw::{Fee, FeeTransfer, WithdrawView};
 
     #[test]
     fn withdrawal_view() {
+        let fee = WithdrawView::new(1_000_000, Some(Fee::new("account_id".parse().unwrap(), 100)));
+
+        assert_eq!(
+            fee,
+            WithdrawView {
+                withdrawn_amount: U128(1_000_000 - 100),
+                fee: U128(100),
+                fee_split: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn withdrawal_view_with_split_fee() {
+        let transfers = vec![
+            FeeTransfer {
+                beneficiary_id: "treasury".parse().unwrap(),
+                amount: U128(70),
+            },
+            FeeTransfer {
+                beneficiary_id: "rewards".parse().unwrap(),
+                amount: U128(30),
+            },
+        ];
+
         let fee = WithdrawView::new(
             1_000_000,
             Some(Fee {
-                beneficiary_id: "account_id".to_string().try_into().unwrap(),
-                amount: 100,
+                transfers: transfers.clone(),
             }),
         );
 
@@ -53,14 +90,100 @@ mod test {
             WithdrawView {
                 withdrawn_amount: U128(1_000_000 - 100),
                 fee: U128(100),
+                fee_split: transfers,
             }
         );
     }
+
+    #[test]
+    fn deserialize_fee_with_single_beneficiary() {
+        let fee: Fee = serde_json::from_str(r#"{"beneficiary_id":"fee","amount":100}"#).unwrap();
+
+        assert_eq!(
+            fee.transfers,
+            vec![FeeTransfer {
+                beneficiary_id: "fee".parse().unwrap(),
+                amount: U128(100),
+            }]
+        );
+
+        let fee: Fee = serde_json::from_str(&serde_json::to_string(&fee).unwrap()).unwrap();
+        assert_eq!(fee.amount(), 100);
+    }
 }
 
+/// Withdrawal fee and its distribution between beneficiaries.
 #[derive(Clone, Debug)]
 #[near(serializers=[json])]
+#[serde(from = "FeeShape")]
 pub struct Fee {
+    pub transfers: Vec<FeeTransfer>,
+}
+
+/// Fee used to have a single beneficiary. Callbacks scheduled before the upgrade
+/// carry it in this shape in their arguments, so it's still accepted.
+#[near(serializers=[json])]
+#[serde(untagged)]
+enum FeeShape {
+    Split {
+        transfers: Vec<FeeTransfer>,
+    },
+    Single {
+        beneficiary_id: AccountId,
+        amount: TokenAmount,
+    },
+}
+
+impl From<FeeShape> for Fee {
+    fn from(value: FeeShape) -> Self {
+        match value {
+            FeeShape::Split { transfers } => Self { transfers },
+            FeeShape::Single { beneficiary_id, amount } => Self::new(beneficiary_id, amount),
+        }
+    }
+}
+
+/// A part of the withdrawal fee transferred to a single beneficiary.
+#[derive(Clone, Debug, PartialEq)]
+#[near(serializers=[json])]
+pub struct FeeTransfer {
     pub beneficiary_id: AccountId,
-    pub amount: TokenAmount,
+    pub amount: U128,
+}
+
+impl Fee {
+    /// Fee which goes entirely to a single beneficiary.
+    #[must_use]
+    pub fn new(beneficiary_id: AccountId, amount: TokenAmount) -> Self {
+        Self {
+            transfers: vec![FeeTransfer {
+                beneficiary_id,
+                amount: U128(amount),
+            }],
+        }
+    }
+
+    #[must_use]
+    pub fn amount(&self) -> TokenAmount {
+        self.transfers.iter().map(|transfer| transfer.amount.0).sum()
+    }
+
+    #[must_use]
+    pub fn is_split(&self) -> bool {
+        self.transfers.len() > 1
+    }
+
+    /// Adds `other` fee to this one, merging transfers to the same beneficiaries.
+    pub fn merge(&mut self, other: Fee) {
+        for transfer in other.transfers {
+            match self
+                .transfers
+                .iter_mut()
+                .find(|existing| existing.beneficiary_id == transfer.beneficiary_id)
+            {
+                Some(existing) => existing.amount.0 += transfer.amount.0,
+                None => self.transfers.push(transfer),
+            }
+        }
+    }
 }